@@ -1,13 +1,20 @@
 use crate::Args;
+use crate::OutputFormat;
 use crate::counts::Counts;
 use crate::error::{CustomError, Result};
-use crate::output::{plot_mismatch_rates, write_counts_npz, write_mismatch_rates};
+use crate::output::{
+    TsvConfig, plot_mismatch_heatmap, plot_mismatch_rates, write_counts_mtx, write_counts_npz,
+    write_mismatch_rates, write_phylip_matrix,
+};
 use crate::reader::SiteReader;
 use crate::reader::packedancestrymap::PackedAncestryMapReader;
+use crate::reader::plink::PlinkBedReader;
 use crate::reader::transposed_packedancestrymap::TransposedPackedAncestryMapReader;
+use crate::reader::bam::BamReader;
+use crate::reader::fasta::FastaReader;
+use crate::reader::vcf::VcfReader;
 use rayon::ThreadPoolBuilder;
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 
@@ -23,10 +30,59 @@ pub enum InputSpec {
         // Parsed 0-based indices of variants to keep
         variant_indices: Option<HashSet<usize>>,
         threads: Option<usize>,
+        // One entry per kept site, giving its block-jackknife block id (see
+        // `Counts::jackknife_summaries`)
+        jackknife_block_ids: Option<Vec<usize>>,
+    },
+    Plink {
+        bed: PathBuf,
+        bim: PathBuf,
+        fam: PathBuf,
+        output_dir: PathBuf,
+        npz: bool,
+        sample_pairs: Option<Vec<(String, String)>>,
+        // Parsed 0-based indices of variants to keep
+        variant_indices: Option<HashSet<usize>>,
+        threads: Option<usize>,
+    },
+    Vcf {
+        path: PathBuf,
+        output_dir: PathBuf,
+        npz: bool,
+        sample_pairs: Option<Vec<(String, String)>>,
+        // Parsed 0-based indices of variants to keep
+        variant_indices: Option<HashSet<usize>>,
+        threads: Option<usize>,
+    },
+    Fasta {
+        path: PathBuf,
+        output_dir: PathBuf,
+        npz: bool,
+        sample_pairs: Option<Vec<(String, String)>>,
+        // Parsed 0-based indices of alignment columns to keep
+        variant_indices: Option<HashSet<usize>>,
+        threads: Option<usize>,
+    },
+    Bam {
+        bam_list: PathBuf,
+        snp: PathBuf,
+        output_dir: PathBuf,
+        npz: bool,
+        sample_pairs: Option<Vec<(String, String)>>,
+        // Parsed 0-based indices of variants to keep
+        variant_indices: Option<HashSet<usize>>,
+        threads: Option<usize>,
+        min_base_quality: u8,
+        min_mapping_quality: u8,
+        seed: u64,
+        // One entry per kept site, giving its block-jackknife block id (see
+        // `Counts::jackknife_summaries`)
+        jackknife_block_ids: Option<Vec<usize>>,
     },
 }
 
 impl InputSpec {
+    #[allow(clippy::too_many_arguments)]
     pub fn from_prefix_packedancestrymap(
         prefix: &str,
         output_dir: &str,
@@ -34,6 +90,7 @@ impl InputSpec {
         sample_pairs: Option<Vec<(String, String)>>,
         variant_indices: Option<HashSet<usize>>,
         threads: Option<usize>,
+        jackknife_block_ids: Option<Vec<usize>>,
     ) -> Self {
         Self::PackedAncestryMap {
             ind: PathBuf::from(prefix.to_string() + ".ind"),
@@ -44,6 +101,92 @@ impl InputSpec {
             sample_pairs,
             variant_indices,
             threads,
+            jackknife_block_ids,
+        }
+    }
+
+    pub fn from_prefix_plink(
+        prefix: &str,
+        output_dir: &str,
+        npz: bool,
+        sample_pairs: Option<Vec<(String, String)>>,
+        variant_indices: Option<HashSet<usize>>,
+        threads: Option<usize>,
+    ) -> Self {
+        Self::Plink {
+            bed: PathBuf::from(prefix.to_string() + ".bed"),
+            bim: PathBuf::from(prefix.to_string() + ".bim"),
+            fam: PathBuf::from(prefix.to_string() + ".fam"),
+            output_dir: PathBuf::from(output_dir.to_string()),
+            npz,
+            sample_pairs,
+            variant_indices,
+            threads,
+        }
+    }
+
+    pub fn from_vcf_path(
+        path: &str,
+        output_dir: &str,
+        npz: bool,
+        sample_pairs: Option<Vec<(String, String)>>,
+        variant_indices: Option<HashSet<usize>>,
+        threads: Option<usize>,
+    ) -> Self {
+        Self::Vcf {
+            path: PathBuf::from(path),
+            output_dir: PathBuf::from(output_dir.to_string()),
+            npz,
+            sample_pairs,
+            variant_indices,
+            threads,
+        }
+    }
+
+    pub fn from_fasta_path(
+        path: &str,
+        output_dir: &str,
+        npz: bool,
+        sample_pairs: Option<Vec<(String, String)>>,
+        variant_indices: Option<HashSet<usize>>,
+        threads: Option<usize>,
+    ) -> Self {
+        Self::Fasta {
+            path: PathBuf::from(path),
+            output_dir: PathBuf::from(output_dir.to_string()),
+            npz,
+            sample_pairs,
+            variant_indices,
+            threads,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_bam_list(
+        bam_list: &str,
+        snp_prefix: &str,
+        output_dir: &str,
+        npz: bool,
+        sample_pairs: Option<Vec<(String, String)>>,
+        variant_indices: Option<HashSet<usize>>,
+        threads: Option<usize>,
+        min_base_quality: u8,
+        min_mapping_quality: u8,
+        seed: u64,
+        jackknife_block_ids: Option<Vec<usize>>,
+    ) -> Self {
+        Self::Bam {
+            bam_list: PathBuf::from(bam_list),
+            snp: PathBuf::from(snp_prefix.to_string() + ".snp"),
+            output_dir: PathBuf::from(output_dir.to_string()),
+            npz,
+            sample_pairs,
+            variant_indices,
+            threads,
+            min_base_quality,
+            min_mapping_quality,
+            seed,
+            jackknife_block_ids,
         }
     }
 
@@ -55,6 +198,25 @@ impl InputSpec {
                 println!("SNP : {}", snp.display());
                 println!();
             }
+            InputSpec::Plink { bed, bim, fam, .. } => {
+                println!("BED : {}", bed.display());
+                println!("BIM : {}", bim.display());
+                println!("FAM : {}", fam.display());
+                println!();
+            }
+            InputSpec::Vcf { path, .. } => {
+                println!("VCF : {}", path.display());
+                println!();
+            }
+            InputSpec::Fasta { path, .. } => {
+                println!("FASTA: {}", path.display());
+                println!();
+            }
+            InputSpec::Bam { bam_list, snp, .. } => {
+                println!("BAMS: {}", bam_list.display());
+                println!("SNP : {}", snp.display());
+                println!();
+            }
         }
     }
 
@@ -68,12 +230,10 @@ impl InputSpec {
                 variant_indices,
                 ..
             } => {
-                // Check .geno header to determine if it's transposed or not
-                let f = File::open(geno).map_err(|e| crate::error::CustomError::ReadWithPath {
-                    source: e,
-                    path: geno.to_path_buf(),
-                })?;
-                let mut reader = BufReader::new(f);
+                // Check .geno header to determine if it's transposed or not. Goes through the
+                // same decompressing reader the actual readers use, so a gzip/BGZF-compressed
+                // .geno file is sniffed by its decompressed header, not its compressed bytes.
+                let mut reader = crate::reader::compression::open_decompressed(geno)?;
                 let buffer =
                     reader
                         .fill_buf()
@@ -84,47 +244,381 @@ impl InputSpec {
                 let header_prefix = &buffer[..buffer.len().min(5)];
 
                 if header_prefix.starts_with(b"GENO") {
-                    let reader =
-                        PackedAncestryMapReader::open(ind, geno, snp, variant_indices.clone())?;
+                    let reader = PackedAncestryMapReader::open(
+                        ind,
+                        geno,
+                        snp,
+                        variant_indices.clone(),
+                        true,
+                    )?;
                     Ok(Box::new(reader))
                 } else if header_prefix.starts_with(b"TGENO") {
                     let reader = TransposedPackedAncestryMapReader::open(
                         ind,
                         geno,
                         snp,
+                        None,
                         variant_indices.clone(),
+                        true,
                     )?;
                     Ok(Box::new(reader))
                 } else {
                     Err(crate::error::CustomError::PackedAncestryMapHeaderPrefix)
                 }
             }
+            InputSpec::Plink {
+                bed,
+                bim,
+                fam,
+                variant_indices,
+                ..
+            } => {
+                let reader = PlinkBedReader::open(bed, bim, fam, None, variant_indices.clone())?;
+                Ok(Box::new(reader))
+            }
+            InputSpec::Vcf {
+                path,
+                variant_indices,
+                ..
+            } => {
+                let reader = VcfReader::open(path, None, variant_indices.clone())?;
+                Ok(Box::new(reader))
+            }
+            InputSpec::Fasta {
+                path,
+                variant_indices,
+                ..
+            } => {
+                let reader = FastaReader::open(path, None, variant_indices.clone())?;
+                Ok(Box::new(reader))
+            }
+            InputSpec::Bam {
+                bam_list,
+                snp,
+                variant_indices,
+                min_base_quality,
+                min_mapping_quality,
+                seed,
+                ..
+            } => {
+                let reader = BamReader::open(
+                    bam_list,
+                    snp,
+                    *min_base_quality,
+                    *min_mapping_quality,
+                    *seed,
+                    variant_indices.clone(),
+                )?;
+                Ok(Box::new(reader))
+            }
         }
     }
 
     pub fn output_dir(&self) -> &Path {
         match self {
             InputSpec::PackedAncestryMap { output_dir, .. } => output_dir.as_path(),
+            InputSpec::Plink { output_dir, .. } => output_dir.as_path(),
+            InputSpec::Vcf { output_dir, .. } => output_dir.as_path(),
+            InputSpec::Fasta { output_dir, .. } => output_dir.as_path(),
+            InputSpec::Bam { output_dir, .. } => output_dir.as_path(),
         }
     }
 
     pub fn npz(&self) -> bool {
         match self {
             InputSpec::PackedAncestryMap { npz, .. } => *npz,
+            InputSpec::Plink { npz, .. } => *npz,
+            InputSpec::Vcf { npz, .. } => *npz,
+            InputSpec::Fasta { npz, .. } => *npz,
+            InputSpec::Bam { npz, .. } => *npz,
         }
     }
 
     pub fn sample_pairs(&self) -> Option<&[(String, String)]> {
         match self {
             InputSpec::PackedAncestryMap { sample_pairs, .. } => sample_pairs.as_deref(),
+            InputSpec::Plink { sample_pairs, .. } => sample_pairs.as_deref(),
+            InputSpec::Vcf { sample_pairs, .. } => sample_pairs.as_deref(),
+            InputSpec::Fasta { sample_pairs, .. } => sample_pairs.as_deref(),
+            InputSpec::Bam { sample_pairs, .. } => sample_pairs.as_deref(),
         }
     }
 
     pub fn threads(&self) -> Option<usize> {
         match self {
             InputSpec::PackedAncestryMap { threads, .. } => *threads,
+            InputSpec::Plink { threads, .. } => *threads,
+            InputSpec::Vcf { threads, .. } => *threads,
+            InputSpec::Fasta { threads, .. } => *threads,
+            InputSpec::Bam { threads, .. } => *threads,
         }
     }
+
+    pub fn jackknife_block_ids(&self) -> Option<&[usize]> {
+        match self {
+            InputSpec::PackedAncestryMap {
+                jackknife_block_ids,
+                ..
+            } => jackknife_block_ids.as_deref(),
+            InputSpec::Plink { .. } => None,
+            InputSpec::Vcf { .. } => None,
+            InputSpec::Fasta { .. } => None,
+            InputSpec::Bam {
+                jackknife_block_ids,
+                ..
+            } => jackknife_block_ids.as_deref(),
+        }
+    }
+
+    /// Per-variant metadata for `--export-plink`/`--export-tgeno`, read from `<prefix>.snp` and
+    /// restricted to the kept variant indices (if any), so it lines up with the reader's sites.
+    /// Only PackedAncestryMap input (GENO or TGENO) carries a `.snp` file in a format this can
+    /// read; every other input variant returns [`CustomError::ExportUnsupportedInput`].
+    pub fn variant_meta(&self) -> Result<Vec<crate::writer::VariantMeta>> {
+        match self {
+            InputSpec::PackedAncestryMap {
+                snp,
+                variant_indices,
+                ..
+            } => {
+                let variants = crate::reader::common::read_eigenstrat_snp_full(snp)?;
+                Ok(match variant_indices {
+                    Some(set) => variants
+                        .into_iter()
+                        .enumerate()
+                        .filter(|(idx, _)| set.contains(idx))
+                        .map(|(_, v)| v)
+                        .collect(),
+                    None => variants,
+                })
+            }
+            InputSpec::Plink { .. }
+            | InputSpec::Vcf { .. }
+            | InputSpec::Fasta { .. }
+            | InputSpec::Bam { .. } => Err(CustomError::ExportUnsupportedInput),
+        }
+    }
+}
+
+/// Writes every remaining site from `reader` to `export_plink` (PLINK `.bed/.bim/.fam`) or
+/// `export_tgeno` (transposed PackedAncestryMap `TGENO`), whichever is set, using `variants` for
+/// the `.bim`/`.snp` sidecar metadata. Exactly one of the two export paths must be `Some`.
+pub fn export_reader(
+    reader: &mut dyn SiteReader,
+    variants: &[crate::writer::VariantMeta],
+    export_plink: Option<&str>,
+    export_tgeno: Option<&str>,
+) -> Result<()> {
+    let samples = reader.samples().to_vec();
+    if let Some(prefix) = export_plink {
+        crate::writer::plink_bed::write_bed(prefix, &samples, variants, reader)
+    } else if let Some(prefix) = export_tgeno {
+        crate::writer::transposed_packedancestrymap::write_tgeno(prefix, &samples, variants, reader)
+    } else {
+        Ok(())
+    }
+}
+
+/// File extensions recognized as direct VCF/BCF input (checked against `args.prefix`, which
+/// doubles as a full path when invoking this reader rather than an EIGENSTRAT-style prefix).
+const VCF_EXTENSIONS: [&str; 3] = [".vcf", ".vcf.gz", ".bcf"];
+
+/// File extensions recognized as direct aligned-multi-FASTA input (checked against
+/// `args.prefix`, same convention as [`VCF_EXTENSIONS`]). Unlike VCF/BCF, `bio::io::fasta`
+/// reads plain text only, so no `.gz` variant is recognized here.
+const FASTA_EXTENSIONS: [&str; 3] = [".fasta", ".fa", ".fna"];
+
+/// Parses a comma-separated list of 1-based, inclusive genomic regions, e.g.
+/// `"1:1000000-2000000,chr7:55000000-55100000"`.
+fn parse_inline_regions(spec: &str) -> Result<Vec<(String, u64, u64)>> {
+    let mut regions = Vec::new();
+    for raw in spec.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let (chrom, range) = raw.split_once(':').ok_or(CustomError::VariantIndexLow)?;
+        let (start_str, end_str) = range.split_once('-').ok_or(CustomError::VariantIndexLow)?;
+        let start: u64 = start_str
+            .parse()
+            .map_err(|e| CustomError::VariantIndexInt {
+                source: e,
+                arg: start_str.to_string(),
+            })?;
+        let end: u64 = end_str.parse().map_err(|e| CustomError::VariantIndexInt {
+            source: e,
+            arg: end_str.to_string(),
+        })?;
+        if start == 0 || end == 0 {
+            return Err(CustomError::VariantIndexLow);
+        }
+        let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
+        regions.push((chrom.to_string(), lo, hi));
+    }
+    Ok(regions)
+}
+
+/// Parses a 3+ column BED file (`chrom`, 0-based `start`, half-open `end`, ...) into the same
+/// 1-based inclusive `(chrom, start, end)` triples [`parse_inline_regions`] produces.
+fn parse_bed_regions(path: &impl AsRef<Path>) -> Result<Vec<(String, u64, u64)>> {
+    let f = std::fs::File::open(path).map_err(|e| CustomError::ReadWithPath {
+        source: e,
+        path: path.as_ref().to_path_buf(),
+    })?;
+    let mut regions = Vec::new();
+    for (line_idx, line) in BufReader::new(f).lines().enumerate() {
+        let line = line.map_err(|e| CustomError::ReadWithPath {
+            source: e,
+            path: path.as_ref().to_path_buf(),
+        })?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("track") || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 3 {
+            return Err(CustomError::VariantIndexBedFields {
+                line_num: line_idx + 1,
+                n_fields: fields.len(),
+            });
+        }
+        let bed_start: u64 = fields[1]
+            .parse()
+            .map_err(|e| CustomError::VariantIndexInt {
+                source: e,
+                arg: fields[1].to_string(),
+            })?;
+        let bed_end: u64 = fields[2]
+            .parse()
+            .map_err(|e| CustomError::VariantIndexInt {
+                source: e,
+                arg: fields[2].to_string(),
+            })?;
+        // BED is 0-based, half-open; convert to the 1-based, inclusive convention used
+        // elsewhere in this module (and matching the .snp file's physical positions).
+        regions.push((fields[0].to_string(), bed_start + 1, bed_end));
+    }
+    Ok(regions)
+}
+
+/// Merges overlapping/adjacent regions per chromosome into a sorted, binary-searchable
+/// interval set, as in granges' `left_overlaps`.
+fn merge_regions(mut regions: Vec<(String, u64, u64)>) -> HashMap<String, Vec<(u64, u64)>> {
+    regions.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+    let mut by_chrom: HashMap<String, Vec<(u64, u64)>> = HashMap::new();
+    for (chrom, start, end) in regions {
+        let intervals = by_chrom.entry(chrom).or_default();
+        match intervals.last_mut() {
+            Some((_, last_end)) if start <= *last_end + 1 => {
+                *last_end = (*last_end).max(end);
+            }
+            _ => intervals.push((start, end)),
+        }
+    }
+    by_chrom
+}
+
+/// Binary-searches a chromosome's sorted, non-overlapping intervals for one containing `pos`.
+fn interval_contains(intervals: &[(u64, u64)], pos: u64) -> bool {
+    let idx = intervals.partition_point(|&(start, _)| start <= pos);
+    idx > 0 && pos <= intervals[idx - 1].1
+}
+
+/// Parses either a comma-separated list of `chrom:start-end` regions or, when `spec` names an
+/// existing file, a BED file, and resolves the result against the `(chrom, pos)` columns of a
+/// `.snp` file into the same `HashSet<usize>` of 0-based indices that [`parse_indices`]
+/// produces, so the two selection mechanisms can be combined freely.
+pub fn parse_regions(spec: &str, snp_path: &impl AsRef<Path>) -> Result<HashSet<usize>> {
+    let regions = if Path::new(spec).is_file() {
+        parse_bed_regions(&spec)?
+    } else {
+        parse_inline_regions(spec)?
+    };
+    let by_chrom = merge_regions(regions);
+
+    let positions = crate::reader::common::read_eigenstrat_snp_positions(snp_path)?;
+
+    let present_chroms: HashSet<&str> = positions.iter().map(|(c, _)| c.as_str()).collect();
+    if let Some(missing) = by_chrom.keys().find(|c| !present_chroms.contains(c.as_str())) {
+        return Err(CustomError::VariantIndexUnknownChrom {
+            chrom: missing.clone(),
+        });
+    }
+
+    let mut indices = HashSet::new();
+    for (idx, (chrom, pos)) in positions.iter().enumerate() {
+        if let Some(intervals) = by_chrom.get(chrom)
+            && interval_contains(intervals, *pos)
+        {
+            indices.insert(idx);
+        }
+    }
+    Ok(indices)
+}
+
+/// How genomic positions are grouped into contiguous blocks for the weighted block-jackknife
+/// (see [`crate::counts::Counts::jackknife_summaries`]). Blocks always reset at chromosome
+/// boundaries.
+#[derive(Debug, Clone, Copy)]
+pub enum JackknifeBlockSpec {
+    /// Each block spans up to this many base pairs.
+    Bp(u64),
+    /// Each block spans up to this many (kept) SNPs.
+    Snps(usize),
+}
+
+/// Parses a jackknife block-size spec like `"5000000bp"` or `"500snps"`/`"500snp"`.
+fn parse_jackknife_block_spec(spec: &str) -> Result<JackknifeBlockSpec> {
+    let spec = spec.trim();
+    if let Some(digits) = spec.strip_suffix("bp") {
+        let width: u64 = digits
+            .trim()
+            .parse()
+            .map_err(|e| CustomError::VariantIndexInt {
+                source: e,
+                arg: digits.to_string(),
+            })?;
+        return Ok(JackknifeBlockSpec::Bp(width));
+    }
+    if let Some(digits) = spec.strip_suffix("snps").or_else(|| spec.strip_suffix("snp")) {
+        let n: usize = digits
+            .trim()
+            .parse()
+            .map_err(|e| CustomError::VariantIndexInt {
+                source: e,
+                arg: digits.to_string(),
+            })?;
+        return Ok(JackknifeBlockSpec::Snps(n));
+    }
+    Err(CustomError::JackknifeBlockSpecSuffix {
+        spec: spec.to_string(),
+    })
+}
+
+/// Assigns each entry of `positions` (already filtered down to kept sites, in reader order) to
+/// a contiguous 0-based jackknife block id per `spec`, resetting whenever the chromosome changes
+/// or the current block reaches its configured width.
+fn compute_jackknife_blocks(spec: JackknifeBlockSpec, positions: &[(String, u64)]) -> Vec<usize> {
+    let mut block_ids = Vec::with_capacity(positions.len());
+    let mut block_id = 0usize;
+    let mut block_start_pos = 0u64;
+    let mut block_snp_count = 0usize;
+    let mut current_chrom: Option<&str> = None;
+
+    for (chrom, pos) in positions {
+        let new_chrom = current_chrom != Some(chrom.as_str());
+        let new_block = match spec {
+            JackknifeBlockSpec::Bp(width) => new_chrom || pos.saturating_sub(block_start_pos) >= width,
+            JackknifeBlockSpec::Snps(n) => new_chrom || block_snp_count >= n,
+        };
+        if new_block {
+            if !block_ids.is_empty() {
+                block_id += 1;
+            }
+            block_start_pos = *pos;
+            block_snp_count = 0;
+            current_chrom = Some(chrom.as_str());
+        }
+        block_ids.push(block_id);
+        block_snp_count += 1;
+    }
+    block_ids
 }
 
 pub fn parse_indices(spec: &str) -> Result<HashSet<usize>> {
@@ -172,10 +666,105 @@ pub fn build_input_spec(args: &Args) -> Result<InputSpec> {
         Some(spec) => Some(parse_indices(spec)?),
         None => None,
     };
+    // Regions are resolved against "<prefix>.snp", so they only apply to the PackedAncestryMap
+    // and BAM input modes (VCF and FASTA have no .snp file to resolve positions against).
+    let is_vcf = VCF_EXTENSIONS.iter().any(|ext| args.prefix.ends_with(ext));
+    let is_fasta = !is_vcf && FASTA_EXTENSIONS.iter().any(|ext| args.prefix.ends_with(ext));
+    // Mirrors the PLINK auto-detection below; needed up front so --jackknife-blocks can be
+    // rejected with a clear error instead of silently resolving against a nonexistent .snp file.
+    let is_plink = !is_vcf
+        && !is_fasta
+        && [".bed", ".bim", ".fam"]
+            .map(|ext| PathBuf::from(args.prefix.clone() + ext))
+            .iter()
+            .all(|path| path.is_file());
+    let region_indices = match &args.regions_spec {
+        Some(spec) if !is_vcf && !is_fasta => {
+            let snp_path = PathBuf::from(args.prefix.clone() + ".snp");
+            Some(parse_regions(spec, &snp_path)?)
+        }
+        _ => None,
+    };
+    let variant_indices = match (variant_indices, region_indices) {
+        (Some(a), Some(b)) => Some(a.union(&b).copied().collect()),
+        (Some(a), None) => Some(a),
+        (None, Some(b)) => Some(b),
+        (None, None) => None,
+    };
     let sample_pairs = match &args.sample_pairs_csv {
         Some(path) => Some(load_sample_pairs_csv(path)?),
         None => None,
     };
+    // Jackknife blocks are resolved against "<prefix>.snp", same restriction as --regions above:
+    // neither VCF, FASTA, nor PLINK1 binary input has a .snp file to draw chrom/pos from.
+    let jackknife_block_ids = match &args.jackknife_blocks {
+        Some(_) if is_vcf || is_fasta || is_plink => {
+            return Err(CustomError::JackknifeBlocksUnsupportedInput);
+        }
+        Some(spec) => {
+            let block_spec = parse_jackknife_block_spec(spec)?;
+            let snp_path = PathBuf::from(args.prefix.clone() + ".snp");
+            let positions = crate::reader::common::read_eigenstrat_snp_positions(&snp_path)?;
+            let kept_positions: Vec<(String, u64)> = match &variant_indices {
+                Some(set) => positions
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(idx, _)| set.contains(idx))
+                    .map(|(_, p)| p)
+                    .collect(),
+                None => positions,
+            };
+            Some(compute_jackknife_blocks(block_spec, &kept_positions))
+        }
+        None => None,
+    };
+    if let Some(bam_list) = &args.bam_list {
+        return Ok(InputSpec::from_bam_list(
+            bam_list,
+            &args.prefix,
+            &args.output_directory,
+            args.npz,
+            sample_pairs,
+            variant_indices,
+            args.threads,
+            args.bam_min_base_quality,
+            args.bam_min_mapping_quality,
+            args.bam_seed,
+            jackknife_block_ids,
+        ));
+    }
+    if is_vcf {
+        return Ok(InputSpec::from_vcf_path(
+            &args.prefix,
+            &args.output_directory,
+            args.npz,
+            sample_pairs,
+            variant_indices,
+            args.threads,
+        ));
+    }
+    if is_fasta {
+        return Ok(InputSpec::from_fasta_path(
+            &args.prefix,
+            &args.output_directory,
+            args.npz,
+            sample_pairs,
+            variant_indices,
+            args.threads,
+        ));
+    }
+    // Auto-detect PLINK1 binary input: only switch to it when all three sidecar files are
+    // present, so a bare EIGENSTRAT prefix (no .bed/.bim/.fam) falls through unchanged below.
+    if is_plink {
+        return Ok(InputSpec::from_prefix_plink(
+            &args.prefix,
+            &args.output_directory,
+            args.npz,
+            sample_pairs,
+            variant_indices,
+            args.threads,
+        ));
+    }
     Ok(InputSpec::from_prefix_packedancestrymap(
         &args.prefix,
         &args.output_directory,
@@ -183,6 +772,7 @@ pub fn build_input_spec(args: &Args) -> Result<InputSpec> {
         sample_pairs,
         variant_indices,
         args.threads,
+        jackknife_block_ids,
     ))
 }
 
@@ -283,12 +873,18 @@ fn resolve_sample_pairs(
     Ok(to_keep)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     reader: &mut dyn SiteReader,
     output_dir: impl AsRef<Path>,
     npz: bool,
     threads: Option<usize>,
     sample_pairs: Option<&[(String, String)]>,
+    jackknife_block_ids: Option<Vec<usize>>,
+    format: OutputFormat,
+    tsv_config: &TsvConfig,
+    mtx: bool,
+    quiet: bool,
 ) -> Result<()> {
     const PARALLEL_THRESHOLD: usize = 500;
     let samples: Vec<String> = reader.samples().to_vec();
@@ -296,17 +892,23 @@ pub fn run(
     let pairs_to_keep = sample_pairs
         .map(|pairs| resolve_sample_pairs(&samples, pairs))
         .transpose()?;
-    let mut counts = Counts::new(samples, pairs_to_keep);
+    let mut counts = Counts::new_with_jackknife_blocks(samples, pairs_to_keep, jackknife_block_ids);
     if (threads.is_none() && counts.n_samples() < PARALLEL_THRESHOLD) || threads == Some(1) {
-        counts = counts.consume_reader(reader)?;
+        counts = counts.consume_reader(reader, quiet)?;
     } else if let Some(n) = threads {
         let pool = ThreadPoolBuilder::new().num_threads(n).build()?;
-        counts = pool.install(|| counts.consume_reader_parallel(reader))?;
+        counts = pool.install(|| counts.consume_reader_parallel(reader, quiet))?;
     } else {
-        counts = counts.consume_reader_parallel(reader)?;
+        counts = counts.consume_reader_parallel(reader, quiet)?;
     }
 
-    if npz {
+    if mtx {
+        println!(
+            "Writing sparse pairwise mismatch/comparison counts to {}...",
+            output_dir.as_ref().display()
+        );
+        write_counts_mtx(&counts, output_dir.as_ref())?;
+    } else if npz {
         let npz_path = output_dir.as_ref().join("mismatch_counts.npz");
         println!(
             "Writing pairwise mismatch counts to {}...",
@@ -314,12 +916,24 @@ pub fn run(
         );
         write_counts_npz(&counts, &npz_path)?;
     } else {
-        let rates_path = output_dir.as_ref().join("mismatch_rates.csv");
-        println!(
-            "Writing pairwise mismatch rates to {}...",
-            rates_path.display()
-        );
-        write_mismatch_rates(&counts, &rates_path)?;
+        match format {
+            OutputFormat::Csv => {
+                let rates_path = output_dir.as_ref().join("mismatch_rates.csv");
+                println!(
+                    "Writing pairwise mismatch rates to {}...",
+                    rates_path.display()
+                );
+                write_mismatch_rates(&counts, &rates_path, tsv_config)?;
+            }
+            OutputFormat::Phylip => {
+                let phylip_path = output_dir.as_ref().join("mismatch_rates.phy");
+                println!(
+                    "Writing pairwise mismatch rate distance matrix to {}...",
+                    phylip_path.display()
+                );
+                write_phylip_matrix(&counts, &phylip_path, &tsv_config.missing)?;
+            }
+        }
     }
 
     let plot_path = output_dir.as_ref().join("mismatch_rates.png");
@@ -328,6 +942,14 @@ pub fn run(
         plot_path.display()
     );
     plot_mismatch_rates(&counts, &plot_path)?;
+
+    const MIN_HEATMAP_OVERLAP: u64 = 30000;
+    let heatmap_path = output_dir.as_ref().join("mismatch_heatmap.png");
+    println!(
+        "Writing pairwise mismatch rate heatmap to {}...",
+        heatmap_path.display()
+    );
+    plot_mismatch_heatmap(&counts, &heatmap_path, MIN_HEATMAP_OVERLAP)?;
     Ok(())
 }
 
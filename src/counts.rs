@@ -7,20 +7,119 @@ use rayon::prelude::*;
 use std::collections::HashSet;
 use std::sync::atomic::{AtomicU64, Ordering};
 
+/// Number of Poisson-bootstrap replicates tracked alongside the ordinary (unweighted) counts.
+pub const N_BOOTSTRAP_REPLICATES: usize = 100;
+// Bank 0 holds the ordinary unweighted counts; banks 1..=N_BOOTSTRAP_REPLICATES hold the
+// Poisson-bootstrap replicates.
+const N_BANKS: usize = N_BOOTSTRAP_REPLICATES + 1;
+
 pub struct Counts {
     samples: Vec<String>,
     n_samples: usize,
-    // Note that there are up to 2 mismatches per site so totals = 2 * n_sites
-    mismatches: Vec<u64>, // Flat (n x n) row-major
-    totals: Vec<u64>,
+    // Note that there are up to 2 mismatches per site so totals = 2 * n_sites.
+    // Each bank is a flat (n x n) row-major matrix; see N_BANKS above.
+    mismatches: Vec<Vec<u64>>,
+    totals: Vec<Vec<u64>>,
     // If Some, only calculate PMRs for pairs where indices_to_count[idx(i, j)] is true
     indices_to_count: Option<Vec<bool>>,
+    // Block-jackknife state (see `jackknife_summaries`). `jackknife_block_ids[site_idx]` gives
+    // the contiguous genomic block a site belongs to; `jackknife_mismatches`/`jackknife_totals`
+    // are one flat (n x n) matrix per block, empty unless jackknife blocks were supplied.
+    jackknife_block_ids: Option<Vec<usize>>,
+    jackknife_mismatches: Vec<Vec<u64>>,
+    jackknife_totals: Vec<Vec<u64>>,
+}
+
+/// Deterministic, seedable PRNG step (SplitMix64) so that bootstrap weights are reproducible
+/// across runs and identical between the serial and parallel consumption paths.
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+fn uniform_from_state(state: u64) -> f64 {
+    (state >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+/// Draws a Poisson(1) weight for replicate `bank` at site `site_idx`, using Knuth's algorithm
+/// seeded deterministically from `(site_idx, bank)` so every pair sees the same weight for a
+/// given site/replicate.
+fn poisson_bootstrap_weight(site_idx: usize, bank: usize) -> u64 {
+    let lambda_exp_neg1 = (-1.0f64).exp(); // e^-lambda for lambda = 1
+    let mut state = splitmix64((site_idx as u64) ^ (bank as u64).wrapping_mul(0x9E3779B97F4A7C15));
+    let mut k = 0u64;
+    let mut p = 1.0;
+    loop {
+        state = splitmix64(state);
+        p *= uniform_from_state(state);
+        if p <= lambda_exp_neg1 {
+            return k;
+        }
+        k += 1;
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn std_deviation(values: &[f64], mean: f64) -> f64 {
+    (values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+}
+
+/// Linear-interpolated percentile of a *sorted* slice, `p` in `[0, 100]`.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.len() == 1 {
+        return sorted[0];
+    }
+    let rank = (p / 100.0) * (sorted.len() - 1) as f64;
+    let lo = rank.floor() as usize;
+    let hi = rank.ceil() as usize;
+    if lo == hi {
+        sorted[lo]
+    } else {
+        let frac = rank - lo as f64;
+        sorted[lo] * (1.0 - frac) + sorted[hi] * frac
+    }
+}
+
+/// Standard error and 95% percentile confidence interval for a pairwise mismatch rate,
+/// estimated via Poisson bootstrap.
+#[derive(Debug, Clone, Copy)]
+pub struct BootstrapSummary {
+    pub se: f32,
+    pub ci_low: f32,
+    pub ci_high: f32,
+}
+
+/// Standard error and 95% CI for a pairwise mismatch rate, estimated via the weighted
+/// delete-one block jackknife (Busing, Meijer & van der Leeden 1999).
+#[derive(Debug, Clone, Copy)]
+pub struct JackknifeSummary {
+    pub se: f32,
+    pub ci_low: f32,
+    pub ci_high: f32,
 }
 
 impl Counts {
     pub fn new(
         samples: Vec<String>,
         pairs_to_indices_to_count: Option<HashSet<(usize, usize)>>,
+    ) -> Self {
+        Self::new_with_jackknife_blocks(samples, pairs_to_indices_to_count, None)
+    }
+
+    /// As [`Counts::new`], but also tracks per-block mismatch/total counts for the weighted
+    /// delete-one block jackknife (see [`Counts::jackknife_summaries`]). `block_ids`, if
+    /// present, must have one entry per site giving its contiguous genomic block, 0-indexed
+    /// and contiguous (i.e. `0..=block_ids.iter().max().unwrap()`).
+    pub fn new_with_jackknife_blocks(
+        samples: Vec<String>,
+        pairs_to_indices_to_count: Option<HashSet<(usize, usize)>>,
+        block_ids: Option<Vec<usize>>,
     ) -> Self {
         let n_samples = samples.len();
         let indices_to_count = pairs_to_indices_to_count.map(|pairs| {
@@ -33,12 +132,19 @@ impl Counts {
             }
             mask
         });
+        let n_blocks = block_ids
+            .as_ref()
+            .and_then(|ids| ids.iter().max())
+            .map_or(0, |&max_id| max_id + 1);
         Self {
             samples,
             n_samples,
-            mismatches: vec![0; n_samples * n_samples],
-            totals: vec![0; n_samples * n_samples],
+            mismatches: (0..N_BANKS).map(|_| vec![0; n_samples * n_samples]).collect(),
+            totals: (0..N_BANKS).map(|_| vec![0; n_samples * n_samples]).collect(),
             indices_to_count,
+            jackknife_block_ids: block_ids,
+            jackknife_mismatches: (0..n_blocks).map(|_| vec![0; n_samples * n_samples]).collect(),
+            jackknife_totals: (0..n_blocks).map(|_| vec![0; n_samples * n_samples]).collect(),
         }
     }
 
@@ -63,13 +169,17 @@ impl Counts {
             .map_or(true, |mask| mask[self.idx(i, j)])
     }
 
-    pub fn consume_reader(mut self, reader: &mut dyn SiteReader) -> Result<Self> {
-        let pb = ProgressBar::new(reader.n_sites() as u64);
+    pub fn consume_reader(mut self, reader: &mut dyn SiteReader, quiet: bool) -> Result<Self> {
+        let pb = if quiet {
+            ProgressBar::hidden()
+        } else {
+            ProgressBar::new(reader.n_sites() as u64)
+        };
         pb.set_style(
-            ProgressStyle::with_template("[{elapsed_precise}] {bar:30} {pos}/{len} sites").unwrap(),
+            ProgressStyle::with_template("[{elapsed_precise}] {bar:30} {pos}/{len} sites (eta {eta})").unwrap(),
         );
 
-        for site in reader {
+        for (site_idx, site) in reader.enumerate() {
             let site = site?;
             let present: Vec<(usize, Allele)> = site
                 .genotypes
@@ -79,6 +189,18 @@ impl Counts {
                 .filter(|&(_, a)| a != Allele::Missing)
                 .collect();
 
+            // Bank 0 is the ordinary (unweighted) count; banks 1..=B are Poisson-bootstrap
+            // replicates sharing one weight per site across all pairs.
+            let bank_weights: [u64; N_BANKS] = std::array::from_fn(|bank| {
+                if bank == 0 {
+                    1
+                } else {
+                    poisson_bootstrap_weight(site_idx, bank)
+                }
+            });
+
+            let block_idx = self.jackknife_block_ids.as_ref().map(|ids| ids[site_idx]);
+
             for (i, &(sample_idx_i, genotype_i)) in present.iter().enumerate() {
                 for &(sample_idx_j, genotype_j) in &present[i + 1..] {
                     let counter_idx = self.idx(sample_idx_i, sample_idx_j);
@@ -87,8 +209,16 @@ impl Counts {
                         .as_ref()
                         .map_or(true, |mask| mask[counter_idx])
                     {
-                        self.mismatches[counter_idx] += genotype_i.mismatch(genotype_j) as u64;
-                        self.totals[counter_idx] += 2; // Two alleles per site
+                        let mismatch = genotype_i.mismatch(genotype_j);
+                        for bank in 0..N_BANKS {
+                            let weight = bank_weights[bank];
+                            self.mismatches[bank][counter_idx] += weight * mismatch;
+                            self.totals[bank][counter_idx] += weight * 2; // Two alleles per site
+                        }
+                        if let Some(block_idx) = block_idx {
+                            self.jackknife_mismatches[block_idx][counter_idx] += mismatch;
+                            self.jackknife_totals[block_idx][counter_idx] += 2;
+                        }
                     }
                 }
             }
@@ -98,60 +228,104 @@ impl Counts {
         Ok(self)
     }
 
-    pub fn consume_reader_parallel(mut self, reader: &mut dyn SiteReader) -> Result<Self> {
+    pub fn consume_reader_parallel(mut self, reader: &mut dyn SiteReader, quiet: bool) -> Result<Self> {
         let n_sites = reader.n_sites();
-        let pb = ProgressBar::new(n_sites as u64);
+        let pb = if quiet {
+            ProgressBar::hidden()
+        } else {
+            ProgressBar::new(n_sites as u64)
+        };
         pb.set_style(
-            ProgressStyle::with_template("[{elapsed_precise}] {bar:30} {pos}/{len} sites").unwrap(),
+            ProgressStyle::with_template("[{elapsed_precise}] {bar:30} {pos}/{len} sites (eta {eta})").unwrap(),
         );
 
         let n_samples = self.n_samples;
-        let mismatches: Vec<AtomicU64> = (0..n_samples * n_samples)
-            .map(|_| AtomicU64::new(0))
+        let mismatches: Vec<Vec<AtomicU64>> = (0..N_BANKS)
+            .map(|_| (0..n_samples * n_samples).map(|_| AtomicU64::new(0)).collect())
+            .collect();
+        let totals: Vec<Vec<AtomicU64>> = (0..N_BANKS)
+            .map(|_| (0..n_samples * n_samples).map(|_| AtomicU64::new(0)).collect())
+            .collect();
+        let n_blocks = self.jackknife_mismatches.len();
+        let jackknife_mismatches: Vec<Vec<AtomicU64>> = (0..n_blocks)
+            .map(|_| (0..n_samples * n_samples).map(|_| AtomicU64::new(0)).collect())
             .collect();
-        let totals: Vec<AtomicU64> = (0..n_samples * n_samples)
-            .map(|_| AtomicU64::new(0))
+        let jackknife_totals: Vec<Vec<AtomicU64>> = (0..n_blocks)
+            .map(|_| (0..n_samples * n_samples).map(|_| AtomicU64::new(0)).collect())
             .collect();
 
-        reader.par_bridge().try_for_each(|site| -> Result<()> {
-            let site = site?;
-            let present: Vec<(usize, Allele)> = site
-                .genotypes
-                .iter()
-                .copied()
-                .enumerate()
-                .filter(|&(_, a)| a != Allele::Missing)
-                .collect();
+        reader
+            .enumerate()
+            .par_bridge()
+            .try_for_each(|(site_idx, site)| -> Result<()> {
+                let site = site?;
+                let present: Vec<(usize, Allele)> = site
+                    .genotypes
+                    .iter()
+                    .copied()
+                    .enumerate()
+                    .filter(|&(_, a)| a != Allele::Missing)
+                    .collect();
 
-            for (i, &(sample_idx_i, genotype_i)) in present.iter().enumerate() {
-                for &(sample_idx_j, genotype_j) in &present[i + 1..] {
-                    let counter_idx = self.idx(sample_idx_i, sample_idx_j);
-                    if self
-                        .indices_to_count
-                        .as_ref()
-                        .map_or(true, |mask| mask[counter_idx])
-                    {
-                        mismatches[counter_idx]
-                            .fetch_add(genotype_i.mismatch(genotype_j) as u64, Ordering::Relaxed);
-                        totals[counter_idx].fetch_add(2, Ordering::Relaxed);
+                let bank_weights: [u64; N_BANKS] = std::array::from_fn(|bank| {
+                    if bank == 0 {
+                        1
+                    } else {
+                        poisson_bootstrap_weight(site_idx, bank)
+                    }
+                });
+
+                let block_idx = self.jackknife_block_ids.as_ref().map(|ids| ids[site_idx]);
+
+                for (i, &(sample_idx_i, genotype_i)) in present.iter().enumerate() {
+                    for &(sample_idx_j, genotype_j) in &present[i + 1..] {
+                        let counter_idx = self.idx(sample_idx_i, sample_idx_j);
+                        if self
+                            .indices_to_count
+                            .as_ref()
+                            .map_or(true, |mask| mask[counter_idx])
+                        {
+                            let mismatch = genotype_i.mismatch(genotype_j);
+                            for bank in 0..N_BANKS {
+                                let weight = bank_weights[bank];
+                                mismatches[bank][counter_idx]
+                                    .fetch_add(weight * mismatch, Ordering::Relaxed);
+                                totals[bank][counter_idx].fetch_add(weight * 2, Ordering::Relaxed);
+                            }
+                            if let Some(block_idx) = block_idx {
+                                jackknife_mismatches[block_idx][counter_idx]
+                                    .fetch_add(mismatch, Ordering::Relaxed);
+                                jackknife_totals[block_idx][counter_idx]
+                                    .fetch_add(2, Ordering::Relaxed);
+                            }
+                        }
                     }
                 }
-            }
-            pb.inc(1);
-            Ok(())
-        })?;
+                pb.inc(1);
+                Ok(())
+            })?;
 
         self.mismatches = mismatches
             .into_iter()
-            .map(|x| x.load(Ordering::Relaxed))
+            .map(|bank| bank.into_iter().map(|x| x.load(Ordering::Relaxed)).collect())
             .collect();
         self.totals = totals
             .into_iter()
-            .map(|x| x.load(Ordering::Relaxed))
+            .map(|bank| bank.into_iter().map(|x| x.load(Ordering::Relaxed)).collect())
+            .collect();
+        self.jackknife_mismatches = jackknife_mismatches
+            .into_iter()
+            .map(|block| block.into_iter().map(|x| x.load(Ordering::Relaxed)).collect())
+            .collect();
+        self.jackknife_totals = jackknife_totals
+            .into_iter()
+            .map(|block| block.into_iter().map(|x| x.load(Ordering::Relaxed)).collect())
             .collect();
 
         pb.abandon();
-        println!();
+        if !quiet {
+            println!();
+        }
         Ok(self)
     }
 
@@ -164,7 +338,7 @@ impl Counts {
     }
 
     pub fn site_overlaps(&self) -> Vec<u64> {
-        self.totals.iter().map(|x| x / 2).collect()
+        self.totals[0].iter().map(|x| x / 2).collect()
     }
 
     pub fn mismatch_rates(&self) -> (Vec<(String, String)>, Vec<f32>) {
@@ -174,23 +348,131 @@ impl Counts {
             for j in (i + 1)..self.n_samples {
                 let idx = self.idx(i, j);
                 pairs[idx] = (self.samples[i].clone(), self.samples[j].clone());
-                if self.totals[idx] == 0 {
+                if self.totals[0][idx] == 0 {
                     rates[idx] = f32::NAN;
                 } else {
-                    rates[idx] = self.mismatches[idx] as f32 / self.totals[idx] as f32;
+                    rates[idx] = self.mismatches[0][idx] as f32 / self.totals[0][idx] as f32;
                 }
             }
         }
         (pairs, rates)
     }
 
+    /// Poisson-bootstrap standard error and 2.5/97.5 percentile CI for every pair, keyed the
+    /// same way as [`Counts::mismatch_rates`]. `None` for a pair means fewer than two of its
+    /// bootstrap replicates had nonzero overlap, so no summary could be estimated.
+    pub fn bootstrap_summaries(&self) -> Vec<Option<BootstrapSummary>> {
+        let mut summaries = vec![None; self.n_samples * self.n_samples];
+        for i in 0..self.n_samples {
+            for j in (i + 1)..self.n_samples {
+                let idx = self.idx(i, j);
+                let mut replicate_rates: Vec<f64> = (1..N_BANKS)
+                    .filter_map(|bank| {
+                        let total = self.totals[bank][idx];
+                        if total == 0 {
+                            None
+                        } else {
+                            Some(self.mismatches[bank][idx] as f64 / total as f64)
+                        }
+                    })
+                    .collect();
+                if replicate_rates.len() < 2 {
+                    continue;
+                }
+                replicate_rates.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                let m = mean(&replicate_rates);
+                let se = std_deviation(&replicate_rates, m);
+                summaries[idx] = Some(BootstrapSummary {
+                    se: se as f32,
+                    ci_low: percentile(&replicate_rates, 2.5) as f32,
+                    ci_high: percentile(&replicate_rates, 97.5) as f32,
+                });
+            }
+        }
+        summaries
+    }
+
+    /// Weighted delete-one block-jackknife standard error and 95% CI for every pair, keyed
+    /// the same way as [`Counts::mismatch_rates`]. Implements the Busing, Meijer & van der
+    /// Leeden (1999) weighted jackknife: for `g` non-empty blocks with per-block mismatches
+    /// `m_b` and overlap totals `t_b`, full estimate `θ = M/T`, leave-one-out estimate
+    /// `θ_(b) = (M - m_b) / (T - t_b)`, weight `h_b = T/t_b`, pseudovalue
+    /// `τ_b = h_b·θ - (h_b - 1)·θ_(b)`, jackknife mean `θ_J = g·θ - Σ(1 - t_b/T)·θ_(b)`, and
+    /// variance `(1/g)·Σ(τ_b - θ_J)² / (h_b - 1)`. Blocks with `t_b = 0` are skipped; `None`
+    /// means fewer than two non-empty blocks remained for a pair, or no blocks were supplied.
+    pub fn jackknife_summaries(&self) -> Vec<Option<JackknifeSummary>> {
+        let mut summaries = vec![None; self.n_samples * self.n_samples];
+        if self.jackknife_block_ids.is_none() {
+            return summaries;
+        }
+        for i in 0..self.n_samples {
+            for j in (i + 1)..self.n_samples {
+                let idx = self.idx(i, j);
+                let total = self.totals[0][idx];
+                if total == 0 {
+                    continue;
+                }
+                let full_mismatches = self.mismatches[0][idx] as f64;
+                let full_total = total as f64;
+                let theta = full_mismatches / full_total;
+
+                let blocks: Vec<(f64, f64)> = self
+                    .jackknife_mismatches
+                    .iter()
+                    .zip(&self.jackknife_totals)
+                    .filter_map(|(m, t)| {
+                        let t_b = t[idx];
+                        if t_b == 0 {
+                            None
+                        } else {
+                            Some((m[idx] as f64, t_b as f64))
+                        }
+                    })
+                    .collect();
+                let g = blocks.len();
+                if g < 2 {
+                    continue;
+                }
+                let g = g as f64;
+
+                let theta_j = g * theta
+                    - blocks
+                        .iter()
+                        .map(|&(m_b, t_b)| {
+                            let theta_loo = (full_mismatches - m_b) / (full_total - t_b);
+                            (1.0 - t_b / full_total) * theta_loo
+                        })
+                        .sum::<f64>();
+
+                let variance = blocks
+                    .iter()
+                    .map(|&(m_b, t_b)| {
+                        let h_b = full_total / t_b;
+                        let theta_loo = (full_mismatches - m_b) / (full_total - t_b);
+                        let pseudovalue = h_b * theta - (h_b - 1.0) * theta_loo;
+                        (pseudovalue - theta_j).powi(2) / (h_b - 1.0)
+                    })
+                    .sum::<f64>()
+                    / g;
+                let se = variance.sqrt();
+
+                summaries[idx] = Some(JackknifeSummary {
+                    se: se as f32,
+                    ci_low: (theta - 1.96 * se) as f32,
+                    ci_high: (theta + 1.96 * se) as f32,
+                });
+            }
+        }
+        summaries
+    }
+
     pub fn mismatches_2d(&self) -> Array2<u64> {
         let mut matrix = Array2::zeros((self.n_samples, self.n_samples));
         for i in 0..self.n_samples {
             for j in (i + 1)..self.n_samples {
                 let idx = self.idx(i, j);
-                matrix[(i, j)] = self.mismatches[idx];
-                matrix[(j, i)] = self.mismatches[idx];
+                matrix[(i, j)] = self.mismatches[0][idx];
+                matrix[(j, i)] = self.mismatches[0][idx];
             }
         }
         matrix
@@ -201,8 +483,8 @@ impl Counts {
         for i in 0..self.n_samples {
             for j in (i + 1)..self.n_samples {
                 let idx = self.idx(i, j);
-                matrix[(i, j)] = self.totals[idx];
-                matrix[(j, i)] = self.totals[idx];
+                matrix[(i, j)] = self.totals[0][idx];
+                matrix[(j, i)] = self.totals[0][idx];
             }
         }
         matrix
@@ -213,8 +495,8 @@ impl Counts {
         for i in 0..self.n_samples {
             for j in (i + 1)..self.n_samples {
                 let idx = self.idx(i, j);
-                matrix[(i, j)] = self.totals[idx] / 2;
-                matrix[(j, i)] = self.totals[idx] / 2;
+                matrix[(i, j)] = self.totals[0][idx] / 2;
+                matrix[(j, i)] = self.totals[0][idx] / 2;
             }
         }
         matrix
@@ -247,4 +529,65 @@ mod tests {
         assert!(counts.should_count_pair(1, 0));
         assert!(!counts.should_count_pair(0, 0));
     }
+
+    #[test]
+    fn bootstrap_summaries_matches_hand_computed_values() {
+        // Only two of the 100 replicate banks get nonzero overlap; every other bank is left at
+        // total=0 and so is excluded from the replicate-rate set below.
+        let mut counts = Counts::new(vec!["A".to_string(), "B".to_string()], None);
+        let idx = counts.idx(0, 1);
+        counts.mismatches[1][idx] = 1; // rate 1/2 = 0.5
+        counts.totals[1][idx] = 2;
+        counts.mismatches[2][idx] = 0; // rate 0/2 = 0.0
+        counts.totals[2][idx] = 2;
+
+        let summary = counts.bootstrap_summaries()[idx].expect("two replicates have overlap");
+        // mean = 0.25, population std_dev = sqrt(((0.5-0.25)^2 + (0-0.25)^2) / 2) = 0.25
+        assert!((summary.se - 0.25).abs() < 1e-4, "se = {}", summary.se);
+        // 2.5th/97.5th percentile, linearly interpolated between the sorted rates [0.0, 0.5]
+        assert!((summary.ci_low - 0.0125).abs() < 1e-4, "ci_low = {}", summary.ci_low);
+        assert!((summary.ci_high - 0.4875).abs() < 1e-4, "ci_high = {}", summary.ci_high);
+    }
+
+    #[test]
+    fn bootstrap_summaries_is_none_with_fewer_than_two_replicates() {
+        let mut counts = Counts::new(vec!["A".to_string(), "B".to_string()], None);
+        let idx = counts.idx(0, 1);
+        counts.mismatches[1][idx] = 1;
+        counts.totals[1][idx] = 2;
+        // Every other replicate bank stays at total=0, so only one replicate has overlap.
+        assert!(counts.bootstrap_summaries()[idx].is_none());
+    }
+
+    #[test]
+    fn jackknife_summaries_matches_hand_computed_values() {
+        // Two non-empty blocks: block 0 has 1 mismatch / 4 total, block 1 has 2 mismatches / 6
+        // total, matching the full (bank 0) count of 3 mismatches / 10 total.
+        let mut counts = Counts::new_with_jackknife_blocks(
+            vec!["A".to_string(), "B".to_string()],
+            None,
+            Some(vec![0, 0, 1, 1, 1]),
+        );
+        let idx = counts.idx(0, 1);
+        counts.mismatches[0][idx] = 3;
+        counts.totals[0][idx] = 10;
+        counts.jackknife_mismatches[0][idx] = 1;
+        counts.jackknife_totals[0][idx] = 4;
+        counts.jackknife_mismatches[1][idx] = 2;
+        counts.jackknife_totals[1][idx] = 6;
+
+        let summary = counts.jackknife_summaries()[idx].expect("two non-empty blocks");
+        // Hand-derived via the Busing/Meijer/van der Leeden formulas in the doc comment above:
+        // theta = 0.3, theta_j = 0.3, variance ~= 0.0016667, se = sqrt(variance) ~= 0.040825.
+        assert!((summary.se - 0.040825).abs() < 1e-3, "se = {}", summary.se);
+        assert!((summary.ci_low - 0.219983).abs() < 1e-3, "ci_low = {}", summary.ci_low);
+        assert!((summary.ci_high - 0.380017).abs() < 1e-3, "ci_high = {}", summary.ci_high);
+    }
+
+    #[test]
+    fn jackknife_summaries_is_none_without_blocks() {
+        let counts = Counts::new(vec!["A".to_string(), "B".to_string()], None);
+        let idx = counts.idx(0, 1);
+        assert!(counts.jackknife_summaries()[idx].is_none());
+    }
 }
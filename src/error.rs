@@ -25,6 +25,19 @@ pub enum CustomError {
     #[error("could not write to CSV")]
     CsvWrite(#[from] csv::Error),
 
+    #[error("could not read {path} as CSV")]
+    CsvRead {
+        #[source]
+        source: csv::Error,
+        path: std::path::PathBuf,
+    },
+
+    #[error("could not create output directory")]
+    OutputDir {
+        #[source]
+        source: std::io::Error,
+    },
+
     #[error("could not plot mismatch distribution")]
     Plot {
         #[source]
@@ -92,11 +105,193 @@ pub enum CustomError {
         expected: usize,
     },
 
+    #[error("expected {expected} whitespace-separated genotype fields (got {n_fields}) in line {line_num} of unpacked EIGENSTRAT .geno file")]
+    EigenstratGenoFields {
+        line_num: usize,
+        n_fields: usize,
+        expected: usize,
+    },
+
+    #[error(
+        "unpacked EIGENSTRAT .geno file has {found} variant lines, but .snp file declares {expected}"
+    )]
+    EigenstratGenoVariantCount { expected: usize, found: usize },
+
     #[error("need at least 2 samples (got {n_samples})")]
     SampleCount { n_samples: usize },
 
     #[error("need at least 1 variant (got {n_variants})")]
     VariantCount { n_variants: usize },
+
+    #[error("requested variant index {idx} but only {n_variants} variants are present")]
+    VariantIndexHigh { idx: usize, n_variants: usize },
+
+    #[error("variant/region indices are 1-based and must be greater than 0")]
+    VariantIndexLow,
+
+    #[error("could not parse variant index {arg:?}")]
+    VariantIndexInt {
+        #[source]
+        source: std::num::ParseIntError,
+        arg: String,
+    },
+
+    #[error("requested region chromosome {chrom:?} not found in .snp file")]
+    VariantIndexUnknownChrom { chrom: String },
+
+    #[error("requested region start {start} is greater than 0 but end {end} is 0")]
+    VariantIndexRegionBounds { start: u64, end: u64 },
+
+    #[error("expected at least 3 whitespace-separated fields (chrom, start, end) on line {line_num} of BED file, got {n_fields}")]
+    VariantIndexBedFields { line_num: usize, n_fields: usize },
+
+    #[error("could not parse sample/variant hash in header")]
+    PackedAncestryMapHeaderHash {
+        #[source]
+        source: std::num::ParseIntError,
+    },
+
+    #[error(
+        "header sample/variant hash mismatch (sample: expected {expected_sample_hash:08x}, found {found_sample_hash:08x}; variant: expected {expected_variant_hash:08x}, found {found_variant_hash:08x})"
+    )]
+    PackedAncestryMapHashMismatch {
+        expected_sample_hash: u32,
+        found_sample_hash: u32,
+        expected_variant_hash: u32,
+        found_variant_hash: u32,
+    },
+
+    #[error("could not open VCF/BCF file {path}")]
+    VcfOpen {
+        #[source]
+        source: rust_htslib::errors::Error,
+        path: std::path::PathBuf,
+    },
+
+    #[error("could not read VCF/BCF record")]
+    VcfRecord {
+        #[source]
+        source: rust_htslib::errors::Error,
+    },
+
+    #[error("sample name in VCF/BCF header is not valid UTF-8")]
+    VcfHeader {
+        #[source]
+        source: std::str::Utf8Error,
+    },
+
+    #[error("could not open BAM/CRAM file {path}")]
+    BamOpen {
+        #[source]
+        source: rust_htslib::errors::Error,
+        path: std::path::PathBuf,
+    },
+
+    #[error("could not fetch region {chrom}:{pos} in {path}")]
+    BamFetch {
+        #[source]
+        source: rust_htslib::errors::Error,
+        path: std::path::PathBuf,
+        chrom: String,
+        pos: u32,
+    },
+
+    #[error("could not read pileup in {path}")]
+    BamPileup {
+        #[source]
+        source: rust_htslib::errors::Error,
+        path: std::path::PathBuf,
+    },
+
+    #[error("chromosome {chrom} (from .snp file) not found in BAM header of {path}")]
+    BamUnknownChrom { path: std::path::PathBuf, chrom: String },
+
+    #[error("expected 2 whitespace-separated fields (sample, BAM path) on line {line_num} of BAM list file, got {n_fields}")]
+    BamListFields { line_num: usize, n_fields: usize },
+
+    #[error("jackknife block spec {spec:?} must end in \"bp\", \"snp\", or \"snps\"")]
+    JackknifeBlockSpecSuffix { spec: String },
+
+    #[error(
+        "--jackknife-blocks requires EIGENSTRAT or BAM input (blocks are resolved against \"<prefix>.snp\"); VCF/BCF and PLINK1 binary input have no .snp file to draw block boundaries from"
+    )]
+    JackknifeBlocksUnsupportedInput,
+
+    #[error(
+        "--export-plink/--export-tgeno require PackedAncestryMap input (GENO or TGENO, with a \"<prefix>.snp\" file to draw variant metadata from); VCF/BCF, PLINK1 binary, FASTA, and BAM input cannot be exported"
+    )]
+    ExportUnsupportedInput,
+
+    #[error(".bed file does not start with the PLINK1 magic bytes (0x6c 0x1b)")]
+    PlinkBedHeaderMagic,
+
+    #[error(".bed file is not in SNP-major mode (third header byte must be 0x01)")]
+    PlinkBedMode,
+
+    #[error(".bed file size does not match header (expected {expected} bytes, found {found})")]
+    PlinkBedFileSize { expected: u64, found: u64 },
+
+    #[error("expected {expected} fields (got {n_fields}) in line {line_num} of .fam file")]
+    PlinkFamFields {
+        line_num: usize,
+        n_fields: usize,
+        expected: usize,
+    },
+
+    #[error("expected {expected} fields (got {n_fields}) in line {line_num} of .bim file")]
+    PlinkBimFields {
+        line_num: usize,
+        n_fields: usize,
+        expected: usize,
+    },
+
+    #[error("could not write Matrix Market output to {path}")]
+    MtxWrite {
+        #[source]
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+
+    #[error("could not open FASTA file {path}")]
+    FastaOpen {
+        #[source]
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+
+    #[error("could not read FASTA record")]
+    FastaRecord {
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error(
+        "FASTA records must all be the same length for alignment-column comparison (record {id:?} has {found} bases, expected {expected})"
+    )]
+    FastaLengthMismatch {
+        id: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("could not decompress {path}")]
+    Decompress {
+        #[source]
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+
+    #[error("sample pairs CSV must have exactly 2 columns (got a different count)")]
+    SamplePairsColumns,
+
+    #[error("sample pairs CSV has no rows")]
+    SamplePairsEmpty,
+
+    #[error("sample {sample:?} appears on both sides of a pair; self-comparisons are not supported")]
+    SamplePairDuplicate { sample: String },
+
+    #[error("sample {sample:?} in sample pairs CSV is not present in the input")]
+    SamplePairUnknownSample { sample: String },
 }
 
 pub type Result<T> = std::result::Result<T, CustomError>;
@@ -4,12 +4,23 @@ mod error;
 mod model;
 mod output;
 mod reader;
+mod writer;
 
 use crate::error::Result;
 use chrono::Local;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use miette::IntoDiagnostic;
 
+/// Plain-text mismatch-rate table format. Ignored when `--npz` is set.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One row per sample pair: "mismatch_rates.csv" (or whatever `--delimiter` produces).
+    #[default]
+    Csv,
+    /// A square symmetric PHYLIP distance matrix: "mismatch_rates.phy".
+    Phylip,
+}
+
 /// Compute pairwise mismatch rates between genetic sequences.
 #[derive(Parser, Debug)]
 #[command(version, about)]
@@ -42,10 +53,82 @@ pub struct Args {
     #[arg(short, long = "variant-indices")]
     variant_indices_spec: Option<String>,
 
+    /// Genomic region(s) to keep, resolved against the (chrom, pos) columns of "<prefix>.snp".
+    /// Either an inline 1-based, inclusive list (e.g. "1:1000000-2000000,chr7:55000000-55100000")
+    /// or the path to a BED file (0-based, half-open intervals). Combines with
+    /// --variant-indices as a union of both selections.
+    #[arg(long = "regions")]
+    regions_spec: Option<String>,
+
     /// Number of threads to use. When run with fewer than 500 samples, defaults to 1.
     /// When run with 500 or more samples, defaults to the number of logical cores.
     #[arg(short, long)]
     threads: Option<usize>,
+
+    /// Two-column (sample ID, BAM/CRAM path) list file. When set, switches to BAM pileup
+    /// pseudo-haploid calling mode; target sites are read from "<prefix>.snp".
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    bam_list: Option<String>,
+
+    /// Minimum base quality (Phred score) for a pileup base to be used in pseudo-haploid
+    /// calling. Only used in BAM mode.
+    #[arg(long, default_value_t = 20)]
+    bam_min_base_quality: u8,
+
+    /// Minimum mapping quality (MAPQ) for a read to be used in pseudo-haploid calling. Only
+    /// used in BAM mode.
+    #[arg(long, default_value_t = 30)]
+    bam_min_mapping_quality: u8,
+
+    /// Seed for the pseudo-haploid base-sampling PRNG. Only used in BAM mode.
+    #[arg(long, default_value_t = 0)]
+    bam_seed: u64,
+
+    /// Block size for weighted block-jackknife standard errors/CIs, e.g. "5000000bp" or
+    /// "500snps". Blocks reset at chromosome boundaries and are resolved against the (chrom,
+    /// pos) columns of "<prefix>.snp" (not available in VCF mode). Omit to skip the jackknife.
+    #[arg(long = "jackknife-blocks")]
+    jackknife_blocks: Option<String>,
+
+    /// Plain-text mismatch-rate table format. Ignored when --npz is set.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    format: OutputFormat,
+
+    /// Field delimiter for the "csv" format. Ignored for "phylip". Defaults to ",".
+    #[arg(long, default_value_t = ',')]
+    delimiter: char,
+
+    /// String written in place of a numeric value for pairs with zero site overlap, instead of
+    /// silently dropping the pair or emitting "NaN". Defaults to "NaN".
+    #[arg(long, default_value_t = String::from("NaN"))]
+    missing_value: String,
+
+    /// Flag to write outputs as sparse Matrix Market coordinate files (mismatch_counts.mtx,
+    /// comparison_counts.mtx, samples.tsv) instead of the dense --npz arrays or plain-text
+    /// table. Only the pairs actually computed (honoring --sample-pairs-csv) are stored, so
+    /// this is cheaper than --npz when a small subset of pairs was requested. Takes priority
+    /// over --npz and --format if both are set.
+    #[arg(long, default_value_t = false)]
+    mtx: bool,
+
+    /// Suppress the per-site progress bar. Useful for scripted/non-interactive runs where the
+    /// bar's carriage-return redraws would otherwise clutter captured logs.
+    #[arg(short, long, default_value_t = false)]
+    quiet: bool,
+
+    /// Export the (possibly subsetted via --variant-indices/--regions) input to PLINK1 binary
+    /// format at this output prefix (writes "<prefix>.bed/.bim/.fam"), instead of computing
+    /// mismatch rates. Only supported for PackedAncestryMap (GENO/TGENO) input. Conflicts with
+    /// --export-tgeno.
+    #[arg(long = "export-plink", conflicts_with = "export_tgeno")]
+    export_plink: Option<String>,
+
+    /// Export the (possibly subsetted via --variant-indices/--regions) input to the transposed
+    /// PackedAncestryMap TGENO format at this output prefix (writes
+    /// "<prefix>.geno/.ind/.snp"), instead of computing mismatch rates. Only supported for
+    /// PackedAncestryMap (GENO/TGENO) input. Conflicts with --export-plink.
+    #[arg(long = "export-tgeno", conflicts_with = "export_plink")]
+    export_tgeno: Option<String>,
 }
 
 fn try_main() -> Result<()> {
@@ -57,12 +140,33 @@ fn try_main() -> Result<()> {
     input_spec.print_paths();
 
     let mut reader = input_spec.open_reader()?;
+
+    if args.export_plink.is_some() || args.export_tgeno.is_some() {
+        let variants = input_spec.variant_meta()?;
+        return cli::export_reader(
+            reader.as_mut(),
+            &variants,
+            args.export_plink.as_deref(),
+            args.export_tgeno.as_deref(),
+        );
+    }
+
+    let tsv_config = output::TsvConfig {
+        delimiter: args.delimiter as u8,
+        missing: args.missing_value.clone(),
+    };
+
     cli::run(
         reader.as_mut(),
         input_spec.output_dir(),
         input_spec.npz(),
         input_spec.threads(),
         input_spec.sample_pairs(),
+        input_spec.jackknife_block_ids().map(|ids| ids.to_vec()),
+        args.format,
+        &tsv_config,
+        args.mtx,
+        args.quiet,
     )?;
     Ok(())
 }
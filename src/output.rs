@@ -3,38 +3,217 @@ use crate::error::{CustomError, Result};
 use plotters::coord::combinators::IntoLogRange;
 use plotters::prelude::*;
 use plotters::style::{FontStyle, register_font};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 
-pub fn write_mismatch_rates(counts: &Counts, path: &str) -> Result<()> {
+/// Delimiter and missing-value formatting for the plain-text mismatch-rate table, analogous to
+/// granges' `TsvConfig`. `missing` is written in place of any numeric column for pairs with zero
+/// site overlap, rather than silently dropping the pair or emitting a bare "NaN".
+#[derive(Debug, Clone)]
+pub struct TsvConfig {
+    pub delimiter: u8,
+    pub missing: String,
+}
+
+impl Default for TsvConfig {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            missing: "NaN".to_string(),
+        }
+    }
+}
+
+fn format_f32(value: f32, missing: &str) -> String {
+    if value.is_nan() {
+        missing.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn write_mismatch_rates(counts: &Counts, path: impl AsRef<Path>, config: &TsvConfig) -> Result<()> {
+    let path = path.as_ref();
     let n_samples = counts.n_samples();
-    let overlaps = counts.overlaps();
+    let overlaps = counts.site_overlaps();
     let (pairs, rates) = counts.mismatch_rates();
+    let bootstrap_summaries = counts.bootstrap_summaries();
+    let jackknife_summaries = counts.jackknife_summaries();
 
-    let mut wtr = csv::Writer::from_path(path)?;
-    wtr.write_record(&["id1", "id2", "n_overlap", "mismatch_rate"])?;
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(config.delimiter)
+        .from_path(path)?;
+    wtr.write_record([
+        "id1",
+        "id2",
+        "n_overlap",
+        "mismatch_rate",
+        "se",
+        "ci_low",
+        "ci_high",
+        "jackknife_se",
+        "jackknife_ci_low",
+        "jackknife_ci_high",
+    ])?;
 
     for i in 0..n_samples {
         for j in (i + 1)..n_samples {
             let counter_idx = counts.idx(i, j);
             let overlap = overlaps[counter_idx];
+            // A pair with zero site overlap has no well-defined rate, SE, or CI; write the
+            // configured missing-value string for all of them instead of a bare "NaN".
+            if overlap == 0 {
+                wtr.write_record([
+                    pairs[counter_idx].0.clone(),
+                    pairs[counter_idx].1.clone(),
+                    "0".to_string(),
+                    config.missing.clone(),
+                    config.missing.clone(),
+                    config.missing.clone(),
+                    config.missing.clone(),
+                    config.missing.clone(),
+                    config.missing.clone(),
+                    config.missing.clone(),
+                ])?;
+                continue;
+            }
             let rate = rates[counter_idx];
-            wtr.serialize((
-                pairs[counter_idx].0.as_str(),
-                pairs[counter_idx].1.as_str(),
-                overlap,
-                rate,
-            ))?;
+            let (se, ci_low, ci_high) = match bootstrap_summaries[counter_idx] {
+                Some(summary) => (summary.se, summary.ci_low, summary.ci_high),
+                None => (f32::NAN, f32::NAN, f32::NAN),
+            };
+            let (jackknife_se, jackknife_ci_low, jackknife_ci_high) =
+                match jackknife_summaries[counter_idx] {
+                    Some(summary) => (summary.se, summary.ci_low, summary.ci_high),
+                    None => (f32::NAN, f32::NAN, f32::NAN),
+                };
+            wtr.write_record([
+                pairs[counter_idx].0.clone(),
+                pairs[counter_idx].1.clone(),
+                overlap.to_string(),
+                format_f32(rate, &config.missing),
+                format_f32(se, &config.missing),
+                format_f32(ci_low, &config.missing),
+                format_f32(ci_high, &config.missing),
+                format_f32(jackknife_se, &config.missing),
+                format_f32(jackknife_ci_low, &config.missing),
+                format_f32(jackknife_ci_high, &config.missing),
+            ])?;
         }
     }
     wtr.flush().map_err(|e| CustomError::Write {
         source: e,
-        path: path.into(),
+        path: path.to_path_buf(),
+    })?;
+    Ok(())
+}
+
+/// Writes a square symmetric PHYLIP-format distance matrix (mismatch rate as the distance), with
+/// sample labels down the first column, suitable for neighbor-joining/tree tools. Uses the same
+/// sample ordering as [`Counts::samples`]/the NPZ `samples.json` output, and writes `missing` in
+/// place of the distance for pairs with zero site overlap.
+pub fn write_phylip_matrix(counts: &Counts, path: impl AsRef<Path>, missing: &str) -> Result<()> {
+    let path = path.as_ref();
+    let n_samples = counts.n_samples();
+    let samples = counts.samples();
+    let overlaps = counts.site_overlaps_2d();
+    let mismatches = counts.mismatches_2d();
+    let totals = counts.totals_2d();
+
+    let mut contents = format!("{n_samples}\n");
+    for i in 0..n_samples {
+        contents.push_str(&samples[i]);
+        for j in 0..n_samples {
+            contents.push(' ');
+            if i == j {
+                contents.push_str("0.0");
+            } else if overlaps[(i, j)] == 0 {
+                contents.push_str(missing);
+            } else {
+                let rate = mismatches[(i, j)] as f32 / totals[(i, j)] as f32;
+                contents.push_str(&rate.to_string());
+            }
+        }
+        contents.push('\n');
+    }
+    std::fs::write(path, contents).map_err(|e| CustomError::Write {
+        source: e,
+        path: path.to_path_buf(),
+    })?;
+    Ok(())
+}
+
+/// Writes pairwise mismatch/comparison counts as a pair of Matrix Market coordinate files
+/// (`mismatch_counts.mtx`, `comparison_counts.mtx`) plus a `samples.tsv`, storing only the
+/// pairs that were actually computed (honoring `--sample-pairs-csv`) rather than a dense
+/// N×N array. Useful when only a small subset of pairs was requested.
+pub fn write_counts_mtx(counts: &Counts, output_dir: impl AsRef<Path>) -> Result<()> {
+    let output_dir = output_dir.as_ref();
+    let n_samples = counts.n_samples();
+    let mismatches = counts.mismatches_2d();
+    let totals = counts.totals_2d();
+
+    let pairs: Vec<(usize, usize)> = (0..n_samples)
+        .flat_map(|i| ((i + 1)..n_samples).map(move |j| (i, j)))
+        .filter(|&(i, j)| counts.should_count_pair(i, j) && totals[(i, j)] > 0)
+        .collect();
+
+    write_mtx_coordinate(
+        &output_dir.join("mismatch_counts.mtx"),
+        n_samples,
+        &pairs,
+        |i, j| mismatches[(i, j)],
+    )?;
+    write_mtx_coordinate(
+        &output_dir.join("comparison_counts.mtx"),
+        n_samples,
+        &pairs,
+        |i, j| totals[(i, j)],
+    )?;
+
+    let samples_path = output_dir.join("samples.tsv");
+    std::fs::write(&samples_path, counts.samples().join("\n") + "\n").map_err(|e| {
+        CustomError::Write {
+            source: e,
+            path: samples_path.clone(),
+        }
+    })?;
+    Ok(())
+}
+
+/// Writes one upper-triangular `%%MatrixMarket matrix coordinate integer symmetric` file,
+/// listing `pairs` (0-based, `i < j`) as 1-based coordinate/value triples.
+fn write_mtx_coordinate(
+    path: &Path,
+    n_samples: usize,
+    pairs: &[(usize, usize)],
+    value_fn: impl Fn(usize, usize) -> u64,
+) -> Result<()> {
+    let file = File::create(path).map_err(|source| CustomError::MtxWrite {
+        source,
+        path: path.to_path_buf(),
+    })?;
+    let mut writer = BufWriter::new(file);
+    (|| -> std::io::Result<()> {
+        writeln!(writer, "%%MatrixMarket matrix coordinate integer symmetric")?;
+        writeln!(writer, "{n_samples} {n_samples} {}", pairs.len())?;
+        for &(i, j) in pairs {
+            writeln!(writer, "{} {} {}", i + 1, j + 1, value_fn(i, j))?;
+        }
+        writer.flush()
+    })()
+    .map_err(|source| CustomError::MtxWrite {
+        source,
+        path: path.to_path_buf(),
     })?;
     Ok(())
 }
 
-pub fn plot_mismatch_rates(counts: &Counts, path: &str) -> Result<()> {
+pub fn plot_mismatch_rates(counts: &Counts, path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
     let (_pairs, rates) = counts.mismatch_rates();
-    let overlaps = counts.overlaps();
+    let overlaps = counts.site_overlaps();
     let filtered_percentages: Vec<f32> = rates
         .iter()
         .zip(overlaps.iter())
@@ -211,3 +390,274 @@ pub fn plot_mismatch_rates(counts: &Counts, path: &str) -> Result<()> {
     })?;
     Ok(())
 }
+
+/// Maps a mismatch rate in `[0, 1]` onto a perceptual dark purple (low) -> yellow (mid) -> red (high)
+/// gradient.
+fn heatmap_color(rate: f32) -> RGBColor {
+    let t = rate.clamp(0.0, 1.0);
+    if t < 0.5 {
+        let u = t / 0.5;
+        RGBColor(
+            (68.0 + u * (253.0 - 68.0)) as u8,
+            (1.0 + u * (231.0 - 1.0)) as u8,
+            (84.0 + u * (37.0 - 84.0)) as u8,
+        )
+    } else {
+        let u = (t - 0.5) / 0.5;
+        RGBColor(
+            (253.0 + u * (180.0 - 253.0)) as u8,
+            (231.0 + u * (4.0 - 231.0)) as u8,
+            (37.0 + u * (38.0 - 37.0)) as u8,
+        )
+    }
+}
+
+const LOW_CONFIDENCE_GRAY: RGBColor = RGBColor(200, 200, 200);
+
+/// Renders the full `n_samples x n_samples` pairwise mismatch-rate matrix as a heatmap, giving a
+/// cluster-level view of relatedness structure across all samples that the 1-D histogram in
+/// [`plot_mismatch_rates`] can't show. Pairs whose overlap falls below `min_overlap` are drawn in
+/// a distinct low-confidence gray, and the diagonal is left blank.
+pub fn plot_mismatch_heatmap(counts: &Counts, path: impl AsRef<Path>, min_overlap: u64) -> Result<()> {
+    let path = path.as_ref();
+    let n_samples = counts.n_samples();
+    let samples = counts.samples();
+    let mismatches = counts.mismatches_2d();
+    let totals = counts.totals_2d();
+    let overlaps = counts.site_overlaps_2d();
+
+    const IBM_PLEX_MONO: &[u8] =
+        include_bytes!("../assets/fonts/ibm-plex-mono/IBMPlexMono-Regular.ttf");
+    register_font("ibm-plex-mono", FontStyle::Normal, IBM_PLEX_MONO).map_err(|_| CustomError::Font)?;
+
+    let cell_px: u32 = 48;
+    let label_area = 280u32;
+    let legend_width = 260u32;
+    let plot_side = cell_px * n_samples as u32;
+
+    let root_area = BitMapBackend::new(
+        path,
+        (plot_side + label_area + legend_width, plot_side + label_area + 80),
+    )
+    .into_drawing_area();
+    root_area.fill(&WHITE).map_err(|e| CustomError::Plot {
+        source: Box::new(e),
+    })?;
+
+    let (heatmap_area, legend_area) = root_area.split_horizontally(plot_side + label_area);
+
+    let mut chart = ChartBuilder::on(&heatmap_area)
+        .set_label_area_size(LabelAreaPosition::Left, label_area)
+        .set_label_area_size(LabelAreaPosition::Bottom, label_area)
+        .margin(20)
+        .caption("Pairwise Mismatch Rate Heatmap", ("ibm-plex-mono", 48))
+        .build_cartesian_2d(0..n_samples, 0..n_samples)
+        .map_err(|e| CustomError::Plot {
+            source: Box::new(e),
+        })?;
+
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .label_style(("ibm-plex-mono", 18))
+        .x_labels(n_samples)
+        .y_labels(n_samples)
+        .x_label_formatter(&|&i| samples.get(i).cloned().unwrap_or_default())
+        .y_label_formatter(&|&i| samples.get(n_samples - 1 - i).cloned().unwrap_or_default())
+        .axis_desc_style(("ibm-plex-mono", 24))
+        .draw()
+        .map_err(|e| CustomError::Plot {
+            source: Box::new(e),
+        })?;
+
+    chart
+        .draw_series((0..n_samples).flat_map(|i| {
+            (0..n_samples).filter_map(move |j| {
+                if i == j {
+                    return None; // Diagonal is left blank
+                }
+                let y = n_samples - 1 - j;
+                let color = if overlaps[(i, j)] < min_overlap {
+                    LOW_CONFIDENCE_GRAY
+                } else if totals[(i, j)] == 0 {
+                    LOW_CONFIDENCE_GRAY
+                } else {
+                    heatmap_color(mismatches[(i, j)] as f32 / totals[(i, j)] as f32)
+                };
+                Some(Rectangle::new([(i, y), (i + 1, y + 1)], color.filled()))
+            })
+        }))
+        .map_err(|e| CustomError::Plot {
+            source: Box::new(e),
+        })?;
+
+    // Color-scale legend: a vertical gradient bar with mismatch-rate tick labels.
+    const LEGEND_STEPS: usize = 100;
+    let mut legend_chart = ChartBuilder::on(&legend_area)
+        .set_label_area_size(LabelAreaPosition::Right, 120)
+        .margin_top(60)
+        .margin_bottom(label_area as i32)
+        .caption("Mismatch rate", ("ibm-plex-mono", 20))
+        .build_cartesian_2d(0..1, 0..LEGEND_STEPS)
+        .map_err(|e| CustomError::Plot {
+            source: Box::new(e),
+        })?;
+
+    legend_chart
+        .configure_mesh()
+        .disable_mesh()
+        .disable_x_axis()
+        .label_style(("ibm-plex-mono", 16))
+        .y_labels(6)
+        .y_label_formatter(&|&y| format!("{:.1}", y as f32 / LEGEND_STEPS as f32))
+        .draw()
+        .map_err(|e| CustomError::Plot {
+            source: Box::new(e),
+        })?;
+
+    legend_chart
+        .draw_series((0..LEGEND_STEPS).map(|step| {
+            let rate = step as f32 / LEGEND_STEPS as f32;
+            Rectangle::new([(0, step), (1, step + 1)], heatmap_color(rate).filled())
+        }))
+        .map_err(|e| CustomError::Plot {
+            source: Box::new(e),
+        })?;
+
+    root_area.present().map_err(|e| CustomError::Plot {
+        source: Box::new(e),
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Allele, Site};
+    use crate::reader::SiteReader;
+    use std::collections::HashSet;
+
+    fn temp_dir(label: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("fastpmr-output-tests-{label}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A fixed sequence of sites, for driving [`Counts::consume_reader`] in tests without
+    /// reaching into `Counts`'s private mismatch/total fields directly.
+    struct FixedSites {
+        samples: Vec<String>,
+        sites: std::vec::IntoIter<Result<Site>>,
+    }
+
+    impl FixedSites {
+        fn new(samples: Vec<&str>, sites: Vec<Vec<Allele>>) -> Self {
+            Self {
+                samples: samples.into_iter().map(String::from).collect(),
+                sites: sites
+                    .into_iter()
+                    .map(|genotypes| Ok(Site { genotypes }))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            }
+        }
+    }
+
+    impl Iterator for FixedSites {
+        type Item = Result<Site>;
+        fn next(&mut self) -> Option<Self::Item> {
+            self.sites.next()
+        }
+    }
+
+    impl SiteReader for FixedSites {
+        fn samples(&self) -> &[String] {
+            &self.samples
+        }
+        fn n_sites(&self) -> usize {
+            self.sites.len()
+        }
+    }
+
+    #[test]
+    fn write_mismatch_rates_reports_overlap_and_missing_value_for_zero_overlap_pairs() {
+        // A and B are genotyped at every site (5 heterozygous sites, each contributing a
+        // mismatch of 1, then 5 homozygous-match sites contributing 0) for a rate of
+        // 5/20 = 0.25 over 10 overlapping sites; C is missing everywhere, so both of its
+        // pairs have zero overlap.
+        let mut sites = vec![vec![Allele::Het, Allele::Het, Allele::Missing]; 5];
+        sites.extend(vec![vec![Allele::Ref, Allele::Ref, Allele::Missing]; 5]);
+        let mut reader = FixedSites::new(vec!["A", "B", "C"], sites);
+        let counts = Counts::new(vec!["A".to_string(), "B".to_string(), "C".to_string()], None)
+            .consume_reader(&mut reader, true)
+            .unwrap();
+
+        let path = temp_dir("csv").join("mismatch_rates.csv");
+        write_mismatch_rates(&counts, &path, &TsvConfig::default()).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let mut lines = contents.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "id1,id2,n_overlap,mismatch_rate,se,ci_low,ci_high,jackknife_se,jackknife_ci_low,jackknife_ci_high"
+        );
+        // A-B: rate 5/20 = 0.25. consume_reader fills every Poisson-bootstrap bank (all 100
+        // replicates get nonzero overlap here), so se/ci are the deterministic bootstrap values;
+        // no jackknife blocks were supplied, so the jackknife columns are "NaN".
+        assert_eq!(
+            lines.next().unwrap(),
+            "A,B,10,0.25,0.08316584,0.077083334,0.375,NaN,NaN,NaN"
+        );
+        // A-C: zero overlap -> every numeric column is the configured missing value
+        assert_eq!(
+            lines.next().unwrap(),
+            "A,C,0,NaN,NaN,NaN,NaN,NaN,NaN,NaN"
+        );
+    }
+
+    #[test]
+    fn write_mismatch_rates_uses_configured_missing_value() {
+        let counts = Counts::new(vec!["A".to_string(), "B".to_string()], None);
+        let config = TsvConfig {
+            delimiter: b',',
+            missing: "NA".to_string(),
+        };
+        let path = temp_dir("missing-value").join("mismatch_rates.csv");
+        write_mismatch_rates(&counts, &path, &config).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().nth(1).unwrap(), "A,B,0,NA,NA,NA,NA,NA,NA,NA");
+    }
+
+    #[test]
+    fn write_counts_mtx_only_includes_computed_pairs_with_overlap() {
+        // A and B are genotyped at every site (3 heterozygous sites, 2 homozygous-match
+        // sites, for 3 mismatches over 5 overlapping sites); C is missing everywhere, so
+        // (A, C) is in indices_to_count but has zero overlap, and (B, C) has no data at all
+        // and also isn't in indices_to_count.
+        let mut sites = vec![vec![Allele::Het, Allele::Het, Allele::Missing]; 3];
+        sites.extend(vec![vec![Allele::Ref, Allele::Ref, Allele::Missing]; 2]);
+        let mut reader = FixedSites::new(vec!["A", "B", "C"], sites);
+
+        let mut indices_to_count = HashSet::new();
+        indices_to_count.insert((0, 1));
+        indices_to_count.insert((0, 2));
+        let counts = Counts::new(
+            vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            Some(indices_to_count),
+        )
+        .consume_reader(&mut reader, true)
+        .unwrap();
+
+        let dir = temp_dir("mtx");
+        write_counts_mtx(&counts, &dir).unwrap();
+
+        let mismatch_mtx = std::fs::read_to_string(dir.join("mismatch_counts.mtx")).unwrap();
+        let mut lines = mismatch_mtx.lines();
+        assert_eq!(lines.next().unwrap(), "%%MatrixMarket matrix coordinate integer symmetric");
+        assert_eq!(lines.next().unwrap(), "3 3 1");
+        assert_eq!(lines.next().unwrap(), "1 2 3");
+        assert!(lines.next().is_none());
+
+        let samples = std::fs::read_to_string(dir.join("samples.tsv")).unwrap();
+        assert_eq!(samples, "A\nB\nC\n");
+    }
+}
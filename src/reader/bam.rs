@@ -0,0 +1,369 @@
+use rust_htslib::bam::record::Record;
+use rust_htslib::bam::{self, IndexedReader, Read as BamRead};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::error::{CustomError, Result};
+use crate::model::{Allele, Site};
+use crate::reader::SiteReader;
+
+const SNP_FIELDS: usize = 6;
+const BAM_LIST_FIELDS: usize = 2;
+
+struct SnpSite {
+    chrom: String,
+    // 0-based position, converted from the .snp file's 1-based physical position.
+    pos: u32,
+    ref_allele: u8,
+    alt_allele: u8,
+}
+
+/// Derives genotypes directly from aligned reads rather than a pre-called genotype matrix, by
+/// pseudo-haploid sampling one random passing base per sample per site -- the standard
+/// low-coverage ancient-DNA approach. Because only one allele is ever sampled, `Het` is never
+/// emitted.
+pub struct BamReader {
+    readers: Vec<IndexedReader>,
+    bam_paths: Vec<PathBuf>,
+    samples: Vec<String>,
+    sites: Vec<SnpSite>,
+    variant_indices_to_keep: Option<HashSet<usize>>,
+    next_variant_idx: usize,
+    min_base_quality: u8,
+    min_mapping_quality: u8,
+    rng_state: u64,
+}
+
+impl BamReader {
+    pub fn open(
+        bam_list_path: &impl AsRef<Path>,
+        snp_path: &impl AsRef<Path>,
+        min_base_quality: u8,
+        min_mapping_quality: u8,
+        seed: u64,
+        variant_indices_to_keep: Option<HashSet<usize>>,
+    ) -> Result<Self> {
+        let (samples, bam_paths) = read_bam_list(bam_list_path)?;
+        if samples.len() < 2 {
+            return Err(CustomError::SampleCount {
+                n_samples: samples.len(),
+            });
+        }
+
+        let sites = read_snp_sites(snp_path)?;
+        if sites.is_empty() {
+            return Err(CustomError::VariantCount { n_variants: 0 });
+        }
+        if let Some(set) = &variant_indices_to_keep
+            && let Some(&bad_idx) = set.iter().max()
+            && bad_idx >= sites.len()
+        {
+            return Err(CustomError::VariantIndexHigh {
+                idx: bad_idx + 1,
+                n_variants: sites.len(),
+            });
+        }
+
+        let readers = bam_paths
+            .iter()
+            .map(|path| {
+                IndexedReader::from_path(path).map_err(|source| CustomError::BamOpen {
+                    source,
+                    path: path.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            readers,
+            bam_paths,
+            samples,
+            sites,
+            variant_indices_to_keep,
+            next_variant_idx: 0,
+            min_base_quality,
+            min_mapping_quality,
+            rng_state: seed,
+        })
+    }
+
+    /// Pseudo-haploid call for one sample at one site: pile up reads at `site`, discard bases
+    /// and reads below the quality thresholds, then sample a single passing base uniformly at
+    /// random and compare it to the site's ref/alt alleles.
+    fn call_site(&mut self, sample_idx: usize, site_idx: usize) -> Result<Allele> {
+        let site = &self.sites[site_idx];
+        let path = self.bam_paths[sample_idx].clone();
+        let reader = &mut self.readers[sample_idx];
+
+        let tid = reader
+            .header()
+            .tid(site.chrom.as_bytes())
+            .ok_or_else(|| CustomError::BamUnknownChrom {
+                path: path.clone(),
+                chrom: site.chrom.clone(),
+            })?;
+        reader
+            .fetch((tid, site.pos, site.pos + 1))
+            .map_err(|source| CustomError::BamFetch {
+                source,
+                path: path.clone(),
+                chrom: site.chrom.clone(),
+                pos: site.pos,
+            })?;
+
+        let min_base_quality = self.min_base_quality;
+        let min_mapping_quality = self.min_mapping_quality;
+        let mut passing_bases: Vec<u8> = Vec::new();
+        let mut pileups = reader.pileup();
+        while let Some(pileup) = pileups.next() {
+            let pileup = pileup.map_err(|source| CustomError::BamPileup {
+                source,
+                path: path.clone(),
+            })?;
+            if pileup.pos() != site.pos {
+                continue;
+            }
+            for alignment in pileup.alignments() {
+                if alignment.is_del() || alignment.is_refskip() {
+                    continue;
+                }
+                let Some(qpos) = alignment.qpos() else {
+                    continue;
+                };
+                let record: &Record = alignment.record();
+                if record.mapq() < min_mapping_quality {
+                    continue;
+                }
+                if record.qual()[qpos] < min_base_quality {
+                    continue;
+                }
+                passing_bases.push(record.seq()[qpos]);
+            }
+        }
+
+        if passing_bases.is_empty() {
+            return Ok(Allele::Missing);
+        }
+
+        self.rng_state = splitmix64(self.rng_state);
+        let draw = uniform_from_state(self.rng_state);
+        let chosen = passing_bases[(draw * passing_bases.len() as f64) as usize];
+
+        Ok(match chosen.to_ascii_uppercase() {
+            b if b == site.ref_allele => Allele::Ref,
+            b if b == site.alt_allele => Allele::Alt,
+            _ => Allele::Missing,
+        })
+    }
+}
+
+impl SiteReader for BamReader {
+    fn samples(&self) -> &[String] {
+        &self.samples
+    }
+
+    fn n_sites(&self) -> usize {
+        match &self.variant_indices_to_keep {
+            Some(set) => set.len(),
+            None => self.sites.len(),
+        }
+    }
+}
+
+impl Iterator for BamReader {
+    type Item = Result<Site>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next_variant_idx < self.sites.len() {
+            let site_idx = self.next_variant_idx;
+            self.next_variant_idx += 1;
+
+            let keep = match &self.variant_indices_to_keep {
+                Some(set) => set.contains(&site_idx),
+                None => true,
+            };
+            if !keep {
+                continue;
+            }
+
+            let mut genotypes = Vec::with_capacity(self.samples.len());
+            for sample_idx in 0..self.samples.len() {
+                match self.call_site(sample_idx, site_idx) {
+                    Ok(allele) => genotypes.push(allele),
+                    Err(e) => {
+                        // Poison iterator to prevent further reads
+                        self.next_variant_idx = self.sites.len();
+                        return Some(Err(e));
+                    }
+                }
+            }
+            return Some(Ok(Site { genotypes }));
+        }
+        None
+    }
+}
+
+/// Deterministic, seedable PRNG step (SplitMix64), mirroring the one used for Poisson-bootstrap
+/// weights in [`crate::counts`], so pseudo-haploid base sampling is reproducible given `--bam-seed`.
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+fn uniform_from_state(state: u64) -> f64 {
+    (state >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+/// Reads a two-column (sample ID, BAM/CRAM path) list file, one sample per line.
+fn read_bam_list(path: &impl AsRef<Path>) -> Result<(Vec<String>, Vec<PathBuf>)> {
+    let f = File::open(path).map_err(|e| CustomError::ReadWithPath {
+        source: e,
+        path: path.as_ref().to_path_buf(),
+    })?;
+    let mut samples = Vec::new();
+    let mut paths = Vec::new();
+    for (line_idx, line) in BufReader::new(f).lines().enumerate() {
+        let line = line.map_err(|e| CustomError::ReadWithPath {
+            source: e,
+            path: path.as_ref().to_path_buf(),
+        })?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != BAM_LIST_FIELDS {
+            return Err(CustomError::BamListFields {
+                line_num: line_idx + 1,
+                n_fields: fields.len(),
+            });
+        }
+        samples.push(fields[0].to_string());
+        paths.push(PathBuf::from(fields[1]));
+    }
+    Ok((samples, paths))
+}
+
+/// Reads `(chrom, pos, ref, alt)` target sites from an EIGENSTRAT-format `.snp` file.
+fn read_snp_sites(path: &impl AsRef<Path>) -> Result<Vec<SnpSite>> {
+    let f = File::open(path).map_err(|e| CustomError::ReadWithPath {
+        source: e,
+        path: path.as_ref().to_path_buf(),
+    })?;
+    let mut sites = Vec::new();
+    for (line_idx, line) in BufReader::new(f).lines().enumerate() {
+        let line = line.map_err(|e| CustomError::ReadWithPath {
+            source: e,
+            path: path.as_ref().to_path_buf(),
+        })?;
+        let line = line.trim();
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != SNP_FIELDS {
+            return Err(CustomError::EigenstratSnpFields {
+                line_num: line_idx + 1,
+                n_fields: fields.len(),
+                expected: SNP_FIELDS,
+            });
+        }
+        let chrom = fields[1].to_string();
+        let phys_pos: u32 = fields[3]
+            .parse()
+            .map_err(|e| CustomError::VariantIndexInt {
+                source: e,
+                arg: fields[3].to_string(),
+            })?;
+        let ref_allele = fields[4].as_bytes()[0].to_ascii_uppercase();
+        let alt_allele = fields[5].as_bytes()[0].to_ascii_uppercase();
+        sites.push(SnpSite {
+            chrom,
+            pos: phys_pos.saturating_sub(1),
+            ref_allele,
+            alt_allele,
+        });
+    }
+    Ok(sites)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn uniform_from_state_stays_in_unit_range() {
+        for state in [0u64, 1, u64::MAX / 2, u64::MAX - 1, u64::MAX] {
+            let draw = uniform_from_state(state);
+            assert!((0.0..1.0).contains(&draw), "draw {draw} out of [0, 1) for state {state}");
+        }
+    }
+
+    #[test]
+    fn sampled_index_from_uniform_draw_never_reaches_len() {
+        // `call_site` indexes `passing_bases[(draw * len) as usize]`; since `draw` must stay
+        // strictly below 1.0, the resulting index must stay strictly below `len` for any draw,
+        // even the largest one `uniform_from_state` can produce.
+        let len = 7usize;
+        for state in [0u64, 1, 12345, u64::MAX / 2, u64::MAX] {
+            let draw = uniform_from_state(splitmix64(state));
+            let idx = (draw * len as f64) as usize;
+            assert!(idx < len, "index {idx} out of bounds for len {len} (draw {draw})");
+        }
+    }
+
+    #[test]
+    fn splitmix64_is_deterministic_and_spreads_inputs() {
+        assert_eq!(splitmix64(42), splitmix64(42));
+        assert_ne!(splitmix64(42), splitmix64(43));
+    }
+
+    #[test]
+    fn read_bam_list_parses_sample_id_and_path_columns() {
+        let dir = std::env::temp_dir().join(format!("fastpmr-bam-list-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bam_list.txt");
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "Sample1 /data/sample1.bam").unwrap();
+        writeln!(file, "Sample2 /data/sample2.bam").unwrap();
+
+        let (samples, paths) = read_bam_list(&path).unwrap();
+        assert_eq!(samples, vec!["Sample1".to_string(), "Sample2".to_string()]);
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/data/sample1.bam"),
+                PathBuf::from("/data/sample2.bam")
+            ]
+        );
+    }
+
+    #[test]
+    fn read_bam_list_rejects_wrong_field_count() {
+        let dir = std::env::temp_dir().join(format!("fastpmr-bam-list-bad-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bam_list.txt");
+        std::fs::write(&path, "Sample1 /data/sample1.bam extra\n").unwrap();
+
+        let err = read_bam_list(&path).unwrap_err();
+        assert!(matches!(err, CustomError::BamListFields { .. }));
+    }
+
+    #[test]
+    fn read_snp_sites_converts_to_zero_based_positions() {
+        let dir = std::env::temp_dir().join(format!("fastpmr-bam-snp-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.snp");
+        std::fs::write(&path, "rs1 chr1 0.0 12345 a g\n").unwrap();
+
+        let sites = read_snp_sites(&path).unwrap();
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].chrom, "chr1");
+        assert_eq!(sites[0].pos, 12344);
+        assert_eq!(sites[0].ref_allele, b'A');
+        assert_eq!(sites[0].alt_allele, b'G');
+    }
+}
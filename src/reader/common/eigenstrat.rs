@@ -1,18 +1,14 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 
 use crate::error::{CustomError, Result};
+use crate::reader::compression::open_decompressed;
 
 pub(crate) const IND_FIELDS: usize = 3;
 pub(crate) const SNP_FIELDS: usize = 6;
 
 pub(crate) fn read_eigenstrat_ind(path: &impl AsRef<Path>) -> Result<Vec<String>> {
-    let f = File::open(path).map_err(|e| CustomError::ReadWithPath {
-        source: e,
-        path: path.as_ref().to_path_buf(),
-    })?;
-    let f = BufReader::new(f);
+    let f = open_decompressed(path)?;
     let mut sample_ids: Vec<String> = Vec::new();
 
     for (line_idx, line) in f.lines().enumerate() {
@@ -36,11 +32,7 @@ pub(crate) fn read_eigenstrat_ind(path: &impl AsRef<Path>) -> Result<Vec<String>
 }
 
 pub(crate) fn read_eigenstrat_snp(path: &impl AsRef<Path>) -> Result<Vec<String>> {
-    let f = File::open(path).map_err(|e| CustomError::ReadWithPath {
-        source: e,
-        path: path.as_ref().to_path_buf(),
-    })?;
-    let f = BufReader::new(f);
+    let f = open_decompressed(path)?;
     let mut variant_ids: Vec<String> = Vec::new();
 
     for (line_idx, line) in f.lines().enumerate() {
@@ -63,30 +55,96 @@ pub(crate) fn read_eigenstrat_snp(path: &impl AsRef<Path>) -> Result<Vec<String>
     Ok(variant_ids)
 }
 
-pub(crate) fn header_hash(sample_ids: &[String], variant_ids: &[String]) -> (String, String) {
-    fn hashone(id: &str) -> u32 {
-        let mut hash: u32 = 0;
-        for &b in id.as_bytes() {
-            if b == b'\0' {
-                break;
-            }
-            hash = hash.wrapping_mul(23).wrapping_add(b as u32);
+/// Reads the `(chrom, physical_pos)` columns of a `.snp` file, in row order, so they can be
+/// resolved against region specs like `chrom:start-end`.
+pub(crate) fn read_eigenstrat_snp_positions(path: &impl AsRef<Path>) -> Result<Vec<(String, u64)>> {
+    let f = open_decompressed(path)?;
+    let mut positions = Vec::new();
+
+    for (line_idx, line) in f.lines().enumerate() {
+        let line = line.map_err(|e| CustomError::ReadWithPath {
+            source: e,
+            path: path.as_ref().to_path_buf(),
+        })?;
+        let line = line.trim();
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != SNP_FIELDS {
+            return Err(CustomError::EigenstratSnpFields {
+                line_num: line_idx + 1,
+                n_fields: fields.len(),
+                expected: SNP_FIELDS,
+            });
         }
-        hash
+        let chrom = fields[1].to_string();
+        let pos: u64 = fields[3].parse().map_err(|e| CustomError::VariantIndexInt {
+            source: e,
+            arg: fields[3].to_string(),
+        })?;
+        positions.push((chrom, pos));
     }
+    Ok(positions)
+}
 
-    fn hasharr(ids: &[String]) -> u32 {
-        let mut hash: u32 = 0;
-        for id in ids {
-            hash = hash.wrapping_mul(17) ^ hashone(id);
+/// Reads the full per-variant metadata of a `.snp` file (id, chrom, genetic position, physical
+/// position), in row order, for round-tripping into [`crate::writer::VariantMeta`]-based writers
+/// such as [`crate::writer::plink_bed::write_bed`] and
+/// [`crate::writer::transposed_packedancestrymap::write_tgeno`].
+pub(crate) fn read_eigenstrat_snp_full(
+    path: &impl AsRef<Path>,
+) -> Result<Vec<crate::writer::VariantMeta>> {
+    let f = open_decompressed(path)?;
+    let mut variants = Vec::new();
+
+    for (line_idx, line) in f.lines().enumerate() {
+        let line = line.map_err(|e| CustomError::ReadWithPath {
+            source: e,
+            path: path.as_ref().to_path_buf(),
+        })?;
+        let line = line.trim();
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != SNP_FIELDS {
+            return Err(CustomError::EigenstratSnpFields {
+                line_num: line_idx + 1,
+                n_fields: fields.len(),
+                expected: SNP_FIELDS,
+            });
         }
-        hash
+        // Genetic position is cosmetic metadata carried through to the output .bim/.snp file;
+        // unlike physical_pos it's never used to resolve indices, so a malformed value falls
+        // back to 0.0 rather than failing the whole conversion.
+        let genetic_pos_cm: f64 = fields[2].parse().unwrap_or(0.0);
+        let physical_pos: u64 = fields[3].parse().map_err(|e| CustomError::VariantIndexInt {
+            source: e,
+            arg: fields[3].to_string(),
+        })?;
+        variants.push(crate::writer::VariantMeta {
+            id: fields[0].to_string(),
+            chrom: fields[1].to_string(),
+            genetic_pos_cm,
+            physical_pos,
+        });
     }
+    Ok(variants)
+}
+
+// EIGENSOFT's per-string/array polynomial hash constants. Tune these (and only these) if a
+// reference file's header hash doesn't match what we compute.
+const HASH_BYTE_MULTIPLIER: u32 = 23;
+const HASH_ACCUMULATE_MULTIPLIER: u32 = 17;
 
-    let sample_hash = hasharr(sample_ids);
-    let variant_hash = hasharr(variant_ids);
-    (
-        format!("{:08x}", sample_hash),
-        format!("{:08x}", variant_hash),
-    )
+/// Computes EIGENSOFT's ordered-list hash: each ID is hashed byte-by-byte with
+/// [`HASH_BYTE_MULTIPLIER`], then the per-ID hashes are folded together with
+/// [`HASH_ACCUMULATE_MULTIPLIER`], all truncated to 32 bits via wrapping arithmetic. Shared by
+/// [`crate::reader::packedancestrymap`] and [`crate::reader::transposed_packedancestrymap`] so
+/// there's one definition of the algorithm instead of one per reader.
+pub(crate) fn header_hash(ids: &[String]) -> u32 {
+    let mut acc: u32 = 0;
+    for id in ids {
+        let mut h: u32 = 0;
+        for &c in id.as_bytes() {
+            h = h.wrapping_mul(HASH_BYTE_MULTIPLIER).wrapping_add(c as u32);
+        }
+        acc = acc.wrapping_mul(HASH_ACCUMULATE_MULTIPLIER).wrapping_add(h);
+    }
+    acc
 }
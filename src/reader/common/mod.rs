@@ -1,5 +1,8 @@
 pub mod eigenstrat;
 pub mod samples;
 
-pub(crate) use eigenstrat::{header_hash, read_eigenstrat_ind, read_eigenstrat_snp};
+pub(crate) use eigenstrat::{
+    header_hash, read_eigenstrat_ind, read_eigenstrat_snp, read_eigenstrat_snp_full,
+    read_eigenstrat_snp_positions,
+};
 pub(crate) use samples::select_samples;
@@ -0,0 +1,240 @@
+use flate2::bufread::MultiGzDecoder;
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::error::{CustomError, Result};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const BGZF_EXTRA_SI: [u8; 2] = *b"BC";
+/// Length of a BGZF block's fixed preamble: the 12-byte gzip header (ID1, ID2, CM, FLG, 4-byte
+/// MTIME, XFL, OS, 2-byte XLEN) plus the 6-byte "BC" extra subfield (SI1, SI2, 2-byte SLEN,
+/// 2-byte BSIZE) that bgzip always writes.
+const BGZF_HEADER_LEN: u64 = 18;
+/// Length of the gzip trailer (CRC32 + ISIZE) every block ends with.
+const GZIP_TRAILER_LEN: u64 = 8;
+
+/// Compression detected from a file's leading bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CompressionFormat {
+    Plain,
+    /// Ordinary gzip: decodable sequentially, but with no block index to seek into.
+    Gzip,
+    /// bgzip's block-gzip variant: a concatenation of independently-inflatable gzip members,
+    /// each carrying a "BC" extra subfield with its own compressed size, so random access is
+    /// possible via [`BgzfBlockIndex`] without inflating the whole file.
+    Bgzf,
+}
+
+/// Peeks the first bytes of `path` to classify it as plain, gzip, or BGZF.
+pub(crate) fn detect_compression(path: &impl AsRef<Path>) -> Result<CompressionFormat> {
+    let f = File::open(path).map_err(|e| CustomError::ReadWithPath {
+        source: e,
+        path: path.as_ref().to_path_buf(),
+    })?;
+    let mut header = [0u8; BGZF_HEADER_LEN as usize];
+    let n = read_prefix(&mut BufReader::new(f), &mut header).map_err(|e| {
+        CustomError::ReadWithPath {
+            source: e,
+            path: path.as_ref().to_path_buf(),
+        }
+    })?;
+    if n < 2 || header[..2] != GZIP_MAGIC {
+        return Ok(CompressionFormat::Plain);
+    }
+    // FLG.FEXTRA (bit 2 of byte 3) signals an extra field; bgzip always sets it.
+    let has_extra = n >= 12 && header[3] & 0x04 != 0;
+    let xlen = u16::from_le_bytes([header[10], header[11]]) as u64;
+    if has_extra && xlen >= 6 && n as u64 >= BGZF_HEADER_LEN && header[12..14] == BGZF_EXTRA_SI {
+        Ok(CompressionFormat::Bgzf)
+    } else {
+        Ok(CompressionFormat::Gzip)
+    }
+}
+
+fn read_prefix(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut n = 0;
+    while n < buf.len() {
+        match reader.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(read) => n += read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(n)
+}
+
+/// Opens `path`, transparently unwrapping gzip/BGZF compression so the EIGENSTRAT text parser
+/// and the sequential packed-binary parser can read `.gz` inputs without caring which. BGZF's
+/// multi-member structure is handled the same as plain gzip here, since `MultiGzDecoder`
+/// already decodes concatenated gzip members back-to-back; callers that need to seek into a
+/// BGZF file by decompressed byte offset should use [`BgzfBlockIndex`] instead.
+pub(crate) fn open_decompressed(path: &impl AsRef<Path>) -> Result<Box<dyn BufRead + Send>> {
+    let format = detect_compression(path)?;
+    let f = File::open(path).map_err(|e| CustomError::ReadWithPath {
+        source: e,
+        path: path.as_ref().to_path_buf(),
+    })?;
+    match format {
+        CompressionFormat::Plain => Ok(Box::new(BufReader::new(f))),
+        CompressionFormat::Gzip | CompressionFormat::Bgzf => Ok(Box::new(BufReader::new(
+            MultiGzDecoder::new(BufReader::new(f)),
+        ))),
+    }
+}
+
+/// One BGZF block's location, letting [`BgzfBlockIndex`] seek to a decompressed byte offset
+/// without inflating every block before it.
+struct BgzfBlock {
+    file_offset: u64,
+    compressed_len: u64,
+    decompressed_start: u64,
+    decompressed_len: u64,
+}
+
+/// Maps decompressed byte offsets to the BGZF block containing them. Built by walking each
+/// block's gzip header (for `BSIZE`, its compressed length) and trailer (for `ISIZE`, its
+/// decompressed length) and seeking past the deflate payload in between, so building the index
+/// never runs the inflate codec itself.
+pub(crate) struct BgzfBlockIndex {
+    blocks: Vec<BgzfBlock>,
+}
+
+impl BgzfBlockIndex {
+    pub(crate) fn build(path: &impl AsRef<Path>) -> Result<Self> {
+        let f = File::open(path).map_err(|e| CustomError::ReadWithPath {
+            source: e,
+            path: path.as_ref().to_path_buf(),
+        })?;
+        let file_len = f
+            .metadata()
+            .map_err(|e| CustomError::ReadWithPath {
+                source: e,
+                path: path.as_ref().to_path_buf(),
+            })?
+            .len();
+        let mut reader = BufReader::new(f);
+
+        let mut blocks = Vec::new();
+        let mut file_offset = 0u64;
+        let mut decompressed_offset = 0u64;
+        while file_offset < file_len {
+            let mut header = [0u8; BGZF_HEADER_LEN as usize];
+            reader
+                .read_exact(&mut header)
+                .map_err(|e| decompress_err(path, e))?;
+            if header[..2] != GZIP_MAGIC || header[12..14] != BGZF_EXTRA_SI {
+                return Err(decompress_err(
+                    path,
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "expected a BGZF block header",
+                    ),
+                ));
+            }
+            let bsize = u16::from_le_bytes([header[16], header[17]]) as u64 + 1;
+            let remaining = bsize
+                .checked_sub(BGZF_HEADER_LEN + GZIP_TRAILER_LEN)
+                .ok_or_else(|| {
+                    decompress_err(
+                        path,
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "BGZF block size smaller than its header and trailer",
+                        ),
+                    )
+                })?;
+            reader
+                .seek_relative(remaining as i64)
+                .map_err(|e| decompress_err(path, e))?;
+            let mut trailer = [0u8; GZIP_TRAILER_LEN as usize];
+            reader
+                .read_exact(&mut trailer)
+                .map_err(|e| decompress_err(path, e))?;
+            let isize = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]) as u64;
+
+            // BGZF streams end with a conventional empty (EOF) block; skip indexing it rather
+            // than recording a zero-length block that nothing can ever land inside.
+            if isize > 0 {
+                blocks.push(BgzfBlock {
+                    file_offset,
+                    compressed_len: bsize,
+                    decompressed_start: decompressed_offset,
+                    decompressed_len: isize,
+                });
+                decompressed_offset += isize;
+            }
+            file_offset += bsize;
+        }
+        Ok(Self { blocks })
+    }
+
+    fn block_index_for_offset(&self, offset: u64) -> usize {
+        self.blocks
+            .partition_point(|b| b.decompressed_start + b.decompressed_len <= offset)
+    }
+
+    /// Reads the single decompressed byte at `offset`, decompressing its containing block (and
+    /// caching it in `cache`) only when the previous read landed in a different block. Only the
+    /// one most recent block is cached.
+    pub(crate) fn read_byte(
+        &self,
+        file: &mut File,
+        path: &impl AsRef<Path>,
+        offset: u64,
+        cache: &mut Option<(usize, Vec<u8>)>,
+    ) -> Result<u8> {
+        let block_idx = self.block_index_for_offset(offset);
+        let block = self.blocks.get(block_idx).ok_or_else(|| {
+            decompress_err(
+                path,
+                std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "offset past end of BGZF stream",
+                ),
+            )
+        })?;
+        if cache.as_ref().map(|(idx, _)| *idx) != Some(block_idx) {
+            *cache = Some((block_idx, decompress_block(file, block, path)?));
+        }
+        let within_block = (offset - block.decompressed_start) as usize;
+        cache
+            .as_ref()
+            .unwrap()
+            .1
+            .get(within_block)
+            .copied()
+            .ok_or_else(|| {
+                decompress_err(
+                    path,
+                    std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "offset past end of block",
+                    ),
+                )
+            })
+    }
+}
+
+fn decompress_block(file: &mut File, block: &BgzfBlock, path: &impl AsRef<Path>) -> Result<Vec<u8>> {
+    file.seek(SeekFrom::Start(block.file_offset))
+        .map_err(|e| decompress_err(path, e))?;
+    let mut compressed = vec![0u8; block.compressed_len as usize];
+    file.read_exact(&mut compressed)
+        .map_err(|e| decompress_err(path, e))?;
+    let mut decoder = GzDecoder::new(&compressed[..]);
+    let mut decompressed = Vec::with_capacity(block.decompressed_len as usize);
+    decoder
+        .read_to_end(&mut decompressed)
+        .map_err(|e| decompress_err(path, e))?;
+    Ok(decompressed)
+}
+
+fn decompress_err(path: &impl AsRef<Path>, source: std::io::Error) -> CustomError {
+    CustomError::Decompress {
+        path: path.as_ref().to_path_buf(),
+        source,
+    }
+}
@@ -1,18 +1,14 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 
 use crate::error::{CustomError, Result};
+use crate::reader::compression::open_decompressed;
 
 pub(super) const IND_FIELDS: usize = 3;
 pub(super) const SNP_FIELDS: usize = 6;
 
 pub(super) fn read_eigenstrat_ind(path: &impl AsRef<Path>) -> Result<Vec<String>> {
-    let f = File::open(path).map_err(|e| CustomError::ReadWithPath {
-        source: e,
-        path: path.as_ref().to_path_buf(),
-    })?;
-    let f = BufReader::new(f);
+    let f = open_decompressed(path)?;
     let mut sample_ids: Vec<String> = Vec::new();
 
     for (line_idx, line) in f.lines().enumerate() {
@@ -36,11 +32,7 @@ pub(super) fn read_eigenstrat_ind(path: &impl AsRef<Path>) -> Result<Vec<String>
 }
 
 pub(super) fn read_eigenstrat_snp(path: &impl AsRef<Path>) -> Result<Vec<String>> {
-    let f = File::open(path).map_err(|e| CustomError::ReadWithPath {
-        source: e,
-        path: path.as_ref().to_path_buf(),
-    })?;
-    let f = BufReader::new(f);
+    let f = open_decompressed(path)?;
     let mut variant_ids: Vec<String> = Vec::new();
 
     for (line_idx, line) in f.lines().enumerate() {
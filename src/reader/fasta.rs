@@ -0,0 +1,214 @@
+use bio::io::fasta;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::error::{CustomError, Result};
+use crate::model::{Allele, Site};
+use crate::reader::SiteReader;
+use crate::reader::common::select_samples;
+
+/// Reads an aligned multi-FASTA (all records the same length) via `bio::io::fasta`, treating
+/// each alignment column as a "variant" and each record as a "sample." This lets mtDNA or
+/// consensus-sequence alignments feed the same pairwise-mismatch pipeline as the genotype-matrix
+/// readers, without a genotype-calling step in between.
+pub struct FastaReader {
+    samples: Vec<String>,
+    // Sample-major, one row per record in original file order (before sample filtering), so
+    // `sample_indices_to_keep` can index straight into it.
+    sequences: Vec<Vec<u8>>,
+    sample_indices_to_keep: Option<Vec<usize>>,
+    variant_indices_to_keep: Option<HashSet<usize>>,
+    next_variant_idx: usize,
+    n_sites: usize,
+}
+
+impl FastaReader {
+    pub fn open(
+        path: &impl AsRef<Path>,
+        samples_to_keep: Option<HashSet<String>>,
+        variant_indices_to_keep: Option<HashSet<usize>>,
+    ) -> Result<Self> {
+        let reader = fasta::Reader::from_file(path).map_err(|source| CustomError::FastaOpen {
+            source,
+            path: path.as_ref().to_path_buf(),
+        })?;
+
+        let mut samples = Vec::new();
+        let mut sequences: Vec<Vec<u8>> = Vec::new();
+        let mut n_sites: Option<usize> = None;
+        for record in reader.records() {
+            let record = record.map_err(|source| CustomError::FastaRecord { source })?;
+            let seq = record.seq();
+            match n_sites {
+                None => n_sites = Some(seq.len()),
+                Some(expected) if seq.len() != expected => {
+                    return Err(CustomError::FastaLengthMismatch {
+                        id: record.id().to_string(),
+                        expected,
+                        found: seq.len(),
+                    });
+                }
+                Some(_) => {}
+            }
+            samples.push(record.id().to_string());
+            sequences.push(seq.to_ascii_uppercase());
+        }
+
+        let n_sites = n_sites.unwrap_or(0);
+        if n_sites < 1 {
+            return Err(CustomError::VariantCount { n_variants: n_sites });
+        }
+        if samples.len() < 2 {
+            return Err(CustomError::SampleCount {
+                n_samples: samples.len(),
+            });
+        }
+        if let Some(set) = &variant_indices_to_keep
+            && let Some(&bad_idx) = set.iter().max()
+            && bad_idx >= n_sites
+        {
+            return Err(CustomError::VariantIndexHigh {
+                idx: bad_idx + 1,
+                n_variants: n_sites,
+            });
+        }
+
+        let (samples, sample_indices_to_keep) = select_samples(samples, samples_to_keep)?;
+
+        Ok(Self {
+            samples,
+            sequences,
+            sample_indices_to_keep,
+            variant_indices_to_keep,
+            next_variant_idx: 0,
+            n_sites,
+        })
+    }
+}
+
+impl SiteReader for FastaReader {
+    fn samples(&self) -> &[String] {
+        &self.samples
+    }
+
+    fn n_sites(&self) -> usize {
+        match &self.variant_indices_to_keep {
+            Some(set) => set.len(),
+            None => self.n_sites,
+        }
+    }
+}
+
+impl Iterator for FastaReader {
+    type Item = Result<Site>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next_variant_idx < self.n_sites {
+            let variant_idx = self.next_variant_idx;
+            self.next_variant_idx += 1;
+
+            let keep = match &self.variant_indices_to_keep {
+                Some(set) => set.contains(&variant_idx),
+                None => true,
+            };
+            if !keep {
+                continue;
+            }
+
+            let indices_to_keep = self.sample_indices_to_keep.as_deref();
+            match column_to_genotypes(&self.sequences, variant_idx, indices_to_keep) {
+                Some(genotypes) => return Some(Ok(Site { genotypes })),
+                // More than two distinct bases in this column; skip it rather than guess,
+                // mirroring how `VcfReader` drops multiallelic records by default.
+                None => continue,
+            }
+        }
+        None
+    }
+}
+
+/// Maps the bases at alignment column `variant_idx` to `Allele`s: the first distinct base seen
+/// (in sample order) becomes `Ref`, the second becomes `Alt`, and `N`/`-`/`.` become `Missing`.
+/// Returns `None` if a third distinct base appears, since that can't be represented biallelically.
+fn column_to_genotypes(
+    sequences: &[Vec<u8>],
+    variant_idx: usize,
+    indices_to_keep: Option<&[usize]>,
+) -> Option<Vec<Allele>> {
+    let mut ref_base: Option<u8> = None;
+    let mut alt_base: Option<u8> = None;
+
+    let bytes_at_column = |sample_idx: usize| sequences[sample_idx][variant_idx];
+
+    let mut base_to_allele = |base: u8| -> Option<Allele> {
+        match base {
+            b'N' | b'-' | b'.' => Some(Allele::Missing),
+            _ => match (ref_base, alt_base) {
+                (None, _) => {
+                    ref_base = Some(base);
+                    Some(Allele::Ref)
+                }
+                (Some(r), _) if base == r => Some(Allele::Ref),
+                (Some(_), None) => {
+                    alt_base = Some(base);
+                    Some(Allele::Alt)
+                }
+                (Some(_), Some(a)) if base == a => Some(Allele::Alt),
+                (Some(_), Some(_)) => None,
+            },
+        }
+    };
+
+    match indices_to_keep {
+        Some(indices) => indices
+            .iter()
+            .map(|&sample_idx| base_to_allele(bytes_at_column(sample_idx)))
+            .collect(),
+        None => (0..sequences.len())
+            .map(|sample_idx| base_to_allele(bytes_at_column(sample_idx)))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_to_genotypes_maps_first_base_to_ref_and_second_to_alt() {
+        let sequences = vec![vec![b'A'], vec![b'A'], vec![b'G'], vec![b'A']];
+        assert_eq!(
+            column_to_genotypes(&sequences, 0, None),
+            Some(vec![Allele::Ref, Allele::Ref, Allele::Alt, Allele::Ref])
+        );
+    }
+
+    #[test]
+    fn column_to_genotypes_treats_n_dash_and_dot_as_missing() {
+        let sequences = vec![vec![b'A'], vec![b'N'], vec![b'-'], vec![b'.']];
+        assert_eq!(
+            column_to_genotypes(&sequences, 0, None),
+            Some(vec![
+                Allele::Ref,
+                Allele::Missing,
+                Allele::Missing,
+                Allele::Missing
+            ])
+        );
+    }
+
+    #[test]
+    fn column_to_genotypes_rejects_a_third_distinct_base() {
+        let sequences = vec![vec![b'A'], vec![b'G'], vec![b'T']];
+        assert_eq!(column_to_genotypes(&sequences, 0, None), None);
+    }
+
+    #[test]
+    fn column_to_genotypes_honors_sample_filter() {
+        let sequences = vec![vec![b'A'], vec![b'G'], vec![b'A']];
+        assert_eq!(
+            column_to_genotypes(&sequences, 0, Some(&[2, 1])),
+            Some(vec![Allele::Ref, Allele::Alt])
+        );
+    }
+}
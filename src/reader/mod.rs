@@ -1,6 +1,12 @@
+pub mod bam;
+pub mod common;
+pub(crate) mod compression;
 pub mod eigenstrat;
+pub mod fasta;
 pub mod packedancestrymap;
+pub mod plink;
 pub mod transposed_packedancestrymap;
+pub mod vcf;
 
 use crate::error::Result;
 use crate::model::Site;
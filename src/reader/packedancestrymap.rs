@@ -1,19 +1,21 @@
 use itertools::Itertools;
 use std::collections::HashSet;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufRead, Read};
 use std::path::Path;
 
 use crate::error::{CustomError, Result};
 use crate::model::{Allele, Site};
 use crate::reader::SiteReader;
+use crate::reader::common::header_hash;
+use crate::reader::compression::open_decompressed;
 use crate::reader::eigenstrat::{read_eigenstrat_ind, read_eigenstrat_snp};
 
 const MIN_BLOCK_BYTES: usize = 48;
 const GENO_HEADER_FIELDS: usize = 5;
 
 pub struct PackedAncestryMapReader {
-    reader: BufReader<File>,
+    reader: Box<dyn BufRead + Send>,
     header: Header,
     samples: Vec<String>,
     variant_indices_to_keep: Option<HashSet<usize>>,
@@ -24,6 +26,8 @@ pub struct PackedAncestryMapReader {
 struct Header {
     n_samples: usize,
     n_variants: usize,
+    sample_hash: u32,
+    variant_hash: u32,
 }
 
 // See https://www.cog-genomics.org/plink/2.0/formats#geno for format description
@@ -33,16 +37,13 @@ impl PackedAncestryMapReader {
         geno_path: &impl AsRef<Path>,
         snp_path: &impl AsRef<Path>,
         variant_indices_to_keep: Option<HashSet<usize>>,
+        verify_hashes: bool,
     ) -> Result<Self> {
         let samples = read_eigenstrat_ind(ind_path)?;
         let variants = read_eigenstrat_snp(snp_path)?;
         let block_size = MIN_BLOCK_BYTES.max(samples.len().div_ceil(4));
 
-        let f = File::open(geno_path).map_err(|e| CustomError::ReadWithPath {
-            source: e,
-            path: geno_path.as_ref().to_path_buf(),
-        })?;
-        let mut reader = BufReader::new(f);
+        let mut reader = open_decompressed(geno_path)?;
 
         // Read header block
         let buffer = reader.fill_buf().map_err(|e| CustomError::ReadWithPath {
@@ -93,6 +94,24 @@ impl PackedAncestryMapReader {
             }
         }
 
+        // Verify the header's EIGENSOFT sample/variant hashes against the IDs we just read.
+        let expected_sample_hash = header_hash(&samples);
+        let expected_variant_hash = header_hash(&variants);
+        if expected_sample_hash != header.sample_hash
+            || expected_variant_hash != header.variant_hash
+        {
+            let err = CustomError::PackedAncestryMapHashMismatch {
+                expected_sample_hash,
+                found_sample_hash: header.sample_hash,
+                expected_variant_hash,
+                found_variant_hash: header.variant_hash,
+            };
+            if verify_hashes {
+                return Err(err);
+            }
+            eprintln!("warning: {err}");
+        }
+
         Ok(Self {
             reader,
             header,
@@ -170,16 +189,16 @@ fn parse_header_block(block: &[u8]) -> Result<Header> {
     let n_variants = fields[2]
         .parse::<usize>()
         .map_err(|e| CustomError::PackedAncestryMapHeaderV { source: e })?;
-
-    // TO-DO: Verify hashes
-    // let hash_samples = fields[3].parse::<u32>()
-    //     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("invalid sample hash: {e}")))?;
-    // let hash_variants = fields[4].parse::<u32>()
-    //     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("invalid variant hash: {e}")))?;
+    let sample_hash = u32::from_str_radix(fields[3], 16)
+        .map_err(|e| CustomError::PackedAncestryMapHeaderHash { source: e })?;
+    let variant_hash = u32::from_str_radix(fields[4], 16)
+        .map_err(|e| CustomError::PackedAncestryMapHeaderHash { source: e })?;
 
     Ok(Header {
         n_samples,
         n_variants,
+        sample_hash,
+        variant_hash,
     })
 }
 
@@ -203,3 +222,90 @@ fn parse_variant_block(block: &[u8], n_samples: usize) -> Vec<Allele> {
     }
     genotypes
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_known_good_geno(
+        path: &Path,
+        samples: &[String],
+        variants: &[String],
+        sample_hash: u32,
+        variant_hash: u32,
+    ) {
+        let block_size = MIN_BLOCK_BYTES.max(samples.len().div_ceil(4));
+        let mut header_block = vec![0u8; block_size];
+        let header_str = format!(
+            "GENO {} {} {:08x} {:08x}",
+            samples.len(),
+            variants.len(),
+            sample_hash,
+            variant_hash
+        );
+        header_block[..header_str.len()].copy_from_slice(header_str.as_bytes());
+
+        let mut file = File::create(path).unwrap();
+        file.write_all(&header_block).unwrap();
+        // One all-Alt (all-zero) variant block is enough to exercise open() end-to-end.
+        file.write_all(&vec![0u8; block_size]).unwrap();
+    }
+
+    #[test]
+    fn header_hash_matches_for_round_tripped_ids() {
+        let samples = vec!["Sample1".to_string(), "Sample2".to_string()];
+        let variants = vec!["rs1".to_string()];
+        assert_eq!(header_hash(&samples), header_hash(&samples.clone()));
+        assert_ne!(header_hash(&samples), header_hash(&variants));
+    }
+
+    #[test]
+    fn open_accepts_matching_hashes() {
+        let dir = std::env::temp_dir().join(format!("fastpmr-hash-ok-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ind_path = dir.join("test.ind");
+        let snp_path = dir.join("test.snp");
+        let geno_path = dir.join("test.geno");
+
+        std::fs::write(&ind_path, "Sample1 U 0\nSample2 U 0\n").unwrap();
+        std::fs::write(&snp_path, "rs1 1 0.0 1 A G\n").unwrap();
+
+        let samples = vec!["Sample1".to_string(), "Sample2".to_string()];
+        let variants = vec!["rs1".to_string()];
+        write_known_good_geno(
+            &geno_path,
+            &samples,
+            &variants,
+            header_hash(&samples),
+            header_hash(&variants),
+        );
+
+        let reader = PackedAncestryMapReader::open(&ind_path, &geno_path, &snp_path, None, true);
+        assert!(reader.is_ok(), "expected matching hashes to open cleanly");
+    }
+
+    #[test]
+    fn open_rejects_mismatched_hashes_when_verifying() {
+        let dir = std::env::temp_dir().join(format!("fastpmr-hash-bad-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ind_path = dir.join("test.ind");
+        let snp_path = dir.join("test.snp");
+        let geno_path = dir.join("test.geno");
+
+        std::fs::write(&ind_path, "Sample1 U 0\nSample2 U 0\n").unwrap();
+        std::fs::write(&snp_path, "rs1 1 0.0 1 A G\n").unwrap();
+
+        let samples = vec!["Sample1".to_string(), "Sample2".to_string()];
+        let variants = vec!["rs1".to_string()];
+        write_known_good_geno(&geno_path, &samples, &variants, 0xdead_beef, 0xdead_beef);
+
+        let err = PackedAncestryMapReader::open(&ind_path, &geno_path, &snp_path, None, true)
+            .unwrap_err();
+        assert!(matches!(err, CustomError::PackedAncestryMapHashMismatch { .. }));
+
+        let reader =
+            PackedAncestryMapReader::open(&ind_path, &geno_path, &snp_path, None, false);
+        assert!(reader.is_ok(), "mismatch should only warn when verify_hashes is false");
+    }
+}
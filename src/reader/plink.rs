@@ -7,7 +7,7 @@ use std::path::Path;
 use crate::error::{CustomError, Result};
 use crate::model::{Allele, Site};
 use crate::reader::SiteReader;
-use crate::reader::sample_filter::select_samples;
+use crate::reader::common::select_samples;
 
 const BED_MAGIC: [u8; 2] = [0x6c, 0x1b];
 const BED_SNP_MAJOR: u8 = 0x01;
@@ -249,3 +249,63 @@ fn decode_sample(bytes: &[u8], sample_idx: usize) -> Allele {
         _ => unreachable!(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_sample_reads_low_bits_first_within_a_byte() {
+        // Samples 0..3 packed into one byte, lowest two bits first: Ref, Missing, Het, Alt.
+        let byte = 0b11_10_01_00u8;
+        assert_eq!(decode_sample(&[byte], 0), Allele::Ref);
+        assert_eq!(decode_sample(&[byte], 1), Allele::Missing);
+        assert_eq!(decode_sample(&[byte], 2), Allele::Het);
+        assert_eq!(decode_sample(&[byte], 3), Allele::Alt);
+    }
+
+    #[test]
+    fn decode_sample_spans_byte_boundaries() {
+        let bytes = [0b00_00_00_00u8, 0b00_00_00_11u8];
+        assert_eq!(decode_sample(&bytes, 4), Allele::Alt);
+    }
+
+    #[test]
+    fn parse_variant_block_honors_sample_filter() {
+        let block = [0b11_10_01_00u8];
+        assert_eq!(
+            parse_variant_block(&block, 4, None),
+            vec![Allele::Ref, Allele::Missing, Allele::Het, Allele::Alt]
+        );
+        assert_eq!(
+            parse_variant_block(&block, 4, Some(&[3, 0])),
+            vec![Allele::Alt, Allele::Ref]
+        );
+    }
+
+    #[test]
+    fn read_plink_fam_joins_family_and_individual_ids_unless_family_is_zero() {
+        let dir = std::env::temp_dir().join(format!("fastpmr-plink-fam-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.fam");
+        std::fs::write(
+            &path,
+            "0 Sample1 0 0 0 -9\nFam2 Sample2 0 0 0 -9\n",
+        )
+        .unwrap();
+
+        let samples = read_plink_fam(&path).unwrap();
+        assert_eq!(samples, vec!["Sample1".to_string(), "Fam2:Sample2".to_string()]);
+    }
+
+    #[test]
+    fn count_plink_bim_rejects_wrong_field_count() {
+        let dir = std::env::temp_dir().join(format!("fastpmr-plink-bim-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.bim");
+        std::fs::write(&path, "1 rs1 0.0 100 A G extra\n").unwrap();
+
+        let err = count_plink_bim(&path).unwrap_err();
+        assert!(matches!(err, CustomError::PlinkBimFields { .. }));
+    }
+}
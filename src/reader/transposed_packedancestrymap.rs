@@ -1,31 +1,64 @@
 use itertools::Itertools;
+use memmap2::Mmap;
 use std::collections::HashSet;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufRead, Read, Seek, SeekFrom};
 use std::path::Path;
 
 use crate::error::{CustomError, Result};
 use crate::model::{Allele, Site};
 use crate::reader::SiteReader;
+use crate::reader::common::{header_hash, select_samples};
+use crate::reader::compression::{
+    BgzfBlockIndex, CompressionFormat, detect_compression, open_decompressed,
+};
+use crate::reader::eigenstrat::{read_eigenstrat_ind, read_eigenstrat_snp};
 
 const HEADER_BLOCK_SIZE: usize = 48;
 const GENO_HEADER_FIELDS: usize = 5;
-const IND_FIELDS: usize = 3;
-const SNP_FIELDS: usize = 6;
+
+/// How the (potentially very large) sample-major genotype matrix is accessed.
+enum GenotypeSource {
+    /// The whole matrix resident in memory; fastest, but can be many GB for large cohorts.
+    InMemory(Vec<u8>),
+    /// The matrix memory-mapped from disk; `genotypes_for_variant` indexes straight into the
+    /// mapped pages instead of an owned buffer.
+    Mapped(Mmap),
+    /// Neither loaded nor mapped: seek to each sample's block on every access. Used as a
+    /// fallback when mmap is unavailable, at the cost of `n_samples` seeks per variant.
+    Streaming(File),
+    /// A BGZF-compressed `.geno` file: seeking is done in decompressed-offset space via a
+    /// pre-built block index, with the most recently decompressed block cached so that
+    /// consecutive reads landing in the same block (e.g. neighboring sample blocks, when they
+    /// are small) don't re-inflate it. The path is kept alongside for error messages.
+    Bgzf(BgzfBlockIndex, File, std::path::PathBuf, Option<(usize, Vec<u8>)>),
+}
+
+/// Selects how [`TransposedPackedAncestryMapReader::open`] accesses the genotype matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccessMode {
+    /// Read the entire matrix into memory up front (fastest for small-to-moderate cohorts).
+    #[default]
+    InMemory,
+    /// Memory-map the file, falling back to seek-per-sample-block streaming if mmap fails.
+    LowMemory,
+}
 
 pub struct TransposedPackedAncestryMapReader {
     header: Header,
     samples: Vec<String>,
     sample_block_size: usize,
+    sample_indices_to_keep: Option<Vec<usize>>,
     variant_indices_to_keep: Option<HashSet<usize>>,
     next_variant_idx: usize,
-    // Entire TGENO matrix (w/o header); length = n_samples * sample_block_size
-    genotype_matrix: Vec<u8>,
+    source: GenotypeSource,
 }
 
 struct Header {
     n_samples: usize,
     n_variants: usize,
+    sample_hash: u32,
+    variant_hash: u32,
 }
 
 // See https://www.cog-genomics.org/plink/2.0/formats#geno for format description
@@ -34,17 +67,36 @@ impl TransposedPackedAncestryMapReader {
         ind_path: &impl AsRef<Path>,
         geno_path: &impl AsRef<Path>,
         snp_path: &impl AsRef<Path>,
+        samples_to_keep: Option<HashSet<String>>,
+        variant_indices_to_keep: Option<HashSet<usize>>,
+        verify_hashes: bool,
+    ) -> Result<Self> {
+        Self::open_with_mode(
+            ind_path,
+            geno_path,
+            snp_path,
+            samples_to_keep,
+            variant_indices_to_keep,
+            AccessMode::InMemory,
+            verify_hashes,
+        )
+    }
+
+    pub fn open_with_mode(
+        ind_path: &impl AsRef<Path>,
+        geno_path: &impl AsRef<Path>,
+        snp_path: &impl AsRef<Path>,
+        samples_to_keep: Option<HashSet<String>>,
         variant_indices_to_keep: Option<HashSet<usize>>,
+        mode: AccessMode,
+        verify_hashes: bool,
     ) -> Result<Self> {
-        let samples = read_ind(ind_path)?;
-        let variants = read_snp(snp_path)?;
+        let samples = read_eigenstrat_ind(ind_path)?;
+        let variants = read_eigenstrat_snp(snp_path)?;
         let sample_block_size = HEADER_BLOCK_SIZE.max(variants.len().div_ceil(4));
 
-        let f = File::open(geno_path).map_err(|e| CustomError::ReadWithPath {
-            source: e,
-            path: geno_path.as_ref().to_path_buf(),
-        })?;
-        let mut reader = BufReader::new(f);
+        let compression = detect_compression(geno_path)?;
+        let mut reader = open_decompressed(geno_path)?;
 
         // Read header block
         let buffer = reader.fill_buf().map_err(|e| CustomError::ReadWithPath {
@@ -95,63 +147,212 @@ impl TransposedPackedAncestryMapReader {
             }
         }
 
-        // Read entire matrix so we can iterate over sites efficiently
+        // Verify the header's EIGENSOFT sample/variant hashes against the IDs we just read,
+        // before `samples` is possibly filtered down below.
+        let expected_sample_hash = header_hash(&samples);
+        let expected_variant_hash = header_hash(&variants);
+        if expected_sample_hash != header.sample_hash
+            || expected_variant_hash != header.variant_hash
+        {
+            let err = CustomError::PackedAncestryMapHashMismatch {
+                expected_sample_hash,
+                found_sample_hash: header.sample_hash,
+                expected_variant_hash,
+                found_variant_hash: header.variant_hash,
+            };
+            if verify_hashes {
+                return Err(err);
+            }
+            eprintln!("warning: {err}");
+        }
+
+        // Overwrite samples
+        // Also record indices of the kept samples so we can read only the relevant sample
+        // blocks later -- the TGENO layout is sample-major, so skipping a sample means
+        // skipping its entire block rather than picking bits out of every variant's block.
+        let (samples, sample_indices_to_keep) = select_samples(samples, samples_to_keep)?;
+
         let expected_bytes = header.n_samples * sample_block_size;
-        let mut genotype_matrix = vec![0u8; expected_bytes];
-        reader
-            .read_exact(&mut genotype_matrix)
-            .map_err(|e| CustomError::ReadWithPath {
-                source: e,
-                path: geno_path.as_ref().to_path_buf(),
-            })?;
-
-        // Ensure no trailing bytes
-        let mut tmp = [0u8; 1];
-        match reader.read(&mut tmp) {
-            Ok(0) => {}
-            Ok(_) => return Err(CustomError::PackedAncestryMapFileSize),
-            Err(e) => {
-                return Err(CustomError::ReadWithPath {
+
+        let source = match (mode, compression) {
+            (AccessMode::InMemory, _) | (AccessMode::LowMemory, CompressionFormat::Gzip) => {
+                // Plain gzip (unlike BGZF) has no block index to seek into, so low-memory
+                // access isn't possible; fall back to reading the whole matrix, same as
+                // AccessMode::InMemory.
+                if mode == AccessMode::LowMemory {
+                    eprintln!(
+                        "warning: {} is gzip-compressed (not bgzip/BGZF), so it has no block \
+                         index to support low-memory access; reading the full genotype matrix \
+                         into memory instead",
+                        geno_path.as_ref().display()
+                    );
+                }
+                let mut genotype_matrix = vec![0u8; expected_bytes];
+                reader
+                    .read_exact(&mut genotype_matrix)
+                    .map_err(|e| CustomError::ReadWithPath {
+                        source: e,
+                        path: geno_path.as_ref().to_path_buf(),
+                    })?;
+                check_no_trailing_bytes(reader.as_mut(), geno_path)?;
+                GenotypeSource::InMemory(genotype_matrix)
+            }
+            (AccessMode::LowMemory, CompressionFormat::Bgzf) => {
+                // Random access goes through a block index built from the compressed file
+                // directly, so `reader`'s decompressing wrapper is only needed for the header
+                // block read above.
+                let index = BgzfBlockIndex::build(geno_path)?;
+                let file = File::open(geno_path).map_err(|e| CustomError::ReadWithPath {
                     source: e,
                     path: geno_path.as_ref().to_path_buf(),
-                });
+                })?;
+                GenotypeSource::Bgzf(index, file, geno_path.as_ref().to_path_buf(), None)
             }
-        }
+            (AccessMode::LowMemory, CompressionFormat::Plain) => {
+                // The header has already been consumed from `reader`'s underlying file handle,
+                // so reopen a fresh handle to map/seek the file from byte 0.
+                let file = File::open(geno_path).map_err(|e| CustomError::ReadWithPath {
+                    source: e,
+                    path: geno_path.as_ref().to_path_buf(),
+                })?;
+                let actual_size = file
+                    .metadata()
+                    .map_err(|e| CustomError::ReadWithPath {
+                        source: e,
+                        path: geno_path.as_ref().to_path_buf(),
+                    })?
+                    .len();
+                if actual_size != (HEADER_BLOCK_SIZE + expected_bytes) as u64 {
+                    return Err(CustomError::PackedAncestryMapFileSize);
+                }
+                // SAFETY: the file is not expected to be mutated by another process while
+                // mapped; this mirrors the read-only, whole-file-resident assumption the
+                // in-memory path already makes.
+                match unsafe { Mmap::map(&file) } {
+                    Ok(mmap) => GenotypeSource::Mapped(mmap),
+                    Err(_) => GenotypeSource::Streaming(file),
+                }
+            }
+        };
 
         Ok(Self {
             header,
             samples,
             sample_block_size,
+            sample_indices_to_keep,
             variant_indices_to_keep,
             next_variant_idx: 0,
-            genotype_matrix,
+            source,
         })
     }
 
-    fn genotypes_for_variant(&self, variant_idx: usize) -> Vec<Allele> {
+    fn genotypes_for_variant(&mut self, variant_idx: usize) -> Result<Vec<Allele>> {
         // For a given sample block, byte (variant_idx / 4) holds 4 genotypes (2 bits each)
         // We shift by 6, 4, 2, or 0 to get the relevant 2 right-most bits
         let byte_idx = variant_idx / 4;
         let shift = 6 - 2 * (variant_idx % 4);
+        let sample_block_size = self.sample_block_size;
+        // Because the TGENO layout is sample-major, a filtered-out sample's entire block is
+        // simply never touched -- no bits need to be picked out of it.
+        let sample_indices = SampleIndices::new(&self.sample_indices_to_keep, self.header.n_samples);
 
-        let n_samples = self.header.n_samples;
-        let mut genotypes = Vec::with_capacity(n_samples);
-
-        // Decode directly from the in-memory matrix
-        for s in 0..n_samples {
-            let sample_block_start = s * self.sample_block_size;
-            let matrix_idx = sample_block_start + byte_idx;
-            let byte = self.genotype_matrix[matrix_idx];
-            let code = (byte >> shift) & 0b11;
-            genotypes.push(match code {
-                0b00 => Allele::Alt,
-                0b01 => Allele::Het,
-                0b10 => Allele::Ref,
-                0b11 => Allele::Missing,
-                _ => unreachable!(),
-            });
+        let mut genotypes = Vec::with_capacity(sample_indices.len());
+        match &mut self.source {
+            GenotypeSource::InMemory(matrix) => {
+                for s in sample_indices {
+                    let matrix_idx = s * sample_block_size + byte_idx;
+                    genotypes.push(decode_byte(matrix[matrix_idx], shift));
+                }
+            }
+            GenotypeSource::Mapped(mmap) => {
+                for s in sample_indices {
+                    // +HEADER_BLOCK_SIZE because the mapping includes the header block.
+                    let matrix_idx = HEADER_BLOCK_SIZE + s * sample_block_size + byte_idx;
+                    genotypes.push(decode_byte(mmap[matrix_idx], shift));
+                }
+            }
+            GenotypeSource::Streaming(file) => {
+                let mut byte_buf = [0u8; 1];
+                for s in sample_indices {
+                    let offset =
+                        (HEADER_BLOCK_SIZE + s * sample_block_size + byte_idx) as u64;
+                    file.seek(SeekFrom::Start(offset))
+                        .map_err(|e| CustomError::ReadWithoutPath { source: e })?;
+                    file.read_exact(&mut byte_buf)
+                        .map_err(|e| CustomError::ReadWithoutPath { source: e })?;
+                    genotypes.push(decode_byte(byte_buf[0], shift));
+                }
+            }
+            GenotypeSource::Bgzf(index, file, path, cache) => {
+                for s in sample_indices {
+                    let offset = (HEADER_BLOCK_SIZE + s * sample_block_size + byte_idx) as u64;
+                    let byte = index.read_byte(file, path, offset, cache)?;
+                    genotypes.push(decode_byte(byte, shift));
+                }
+            }
+        }
+        Ok(genotypes)
+    }
+}
+
+/// Iterates either the kept sample indices (in order) or, when no filter was given, every
+/// index from 0..n_samples -- letting `genotypes_for_variant` skip filtered-out sample blocks
+/// entirely without allocating in the common (unfiltered) case.
+enum SampleIndices<'a> {
+    Kept(std::slice::Iter<'a, usize>),
+    All(std::ops::Range<usize>),
+}
+
+impl<'a> SampleIndices<'a> {
+    fn new(kept: &'a Option<Vec<usize>>, n_samples: usize) -> Self {
+        match kept {
+            Some(indices) => SampleIndices::Kept(indices.iter()),
+            None => SampleIndices::All(0..n_samples),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            SampleIndices::Kept(iter) => iter.len(),
+            SampleIndices::All(range) => range.len(),
         }
-        genotypes
+    }
+}
+
+impl Iterator for SampleIndices<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        match self {
+            SampleIndices::Kept(iter) => iter.next().copied(),
+            SampleIndices::All(range) => range.next(),
+        }
+    }
+}
+
+fn decode_byte(byte: u8, shift: u32) -> Allele {
+    let code = (byte >> shift) & 0b11;
+    match code {
+        0b00 => Allele::Alt,
+        0b01 => Allele::Het,
+        0b10 => Allele::Ref,
+        0b11 => Allele::Missing,
+        _ => unreachable!(),
+    }
+}
+
+fn check_no_trailing_bytes(
+    reader: &mut dyn BufRead,
+    geno_path: &impl AsRef<Path>,
+) -> Result<()> {
+    let mut tmp = [0u8; 1];
+    match reader.read(&mut tmp) {
+        Ok(0) => Ok(()),
+        Ok(_) => Err(CustomError::PackedAncestryMapFileSize),
+        Err(e) => Err(CustomError::ReadWithPath {
+            source: e,
+            path: geno_path.as_ref().to_path_buf(),
+        }),
     }
 }
 
@@ -178,74 +379,24 @@ impl Iterator for TransposedPackedAncestryMapReader {
                 None => true,
             };
 
-            let genotypes = self.genotypes_for_variant(self.next_variant_idx);
+            let variant_idx = self.next_variant_idx;
             self.next_variant_idx += 1;
 
             if keep {
-                return Some(Ok(Site { genotypes }));
+                return match self.genotypes_for_variant(variant_idx) {
+                    Ok(genotypes) => Some(Ok(Site { genotypes })),
+                    Err(e) => {
+                        // Poison iterator to prevent further reads
+                        self.next_variant_idx = self.header.n_variants;
+                        Some(Err(e))
+                    }
+                };
             }
         }
         None
     }
 }
 
-fn read_ind(path: &impl AsRef<Path>) -> Result<Vec<String>> {
-    let f = File::open(path).map_err(|e| CustomError::ReadWithPath {
-        source: e,
-        path: path.as_ref().to_path_buf(),
-    })?;
-    let f = BufReader::new(f);
-    let mut sample_ids: Vec<String> = Vec::new();
-
-    for (line_idx, line) in f.lines().enumerate() {
-        let line = line.map_err(|e| CustomError::ReadWithPath {
-            source: e,
-            path: path.as_ref().to_path_buf(),
-        })?;
-        let line = line.trim();
-        let fields: Vec<&str> = line.split_whitespace().collect();
-        if fields.len() != IND_FIELDS {
-            return Err(CustomError::EigenstratIndFields {
-                line_num: line_idx + 1,
-                n_fields: fields.len(),
-                expected: IND_FIELDS,
-            });
-        }
-        let sample_id = fields[0].to_string();
-        sample_ids.push(sample_id);
-    }
-    Ok(sample_ids)
-}
-
-fn read_snp(path: &impl AsRef<Path>) -> Result<Vec<String>> {
-    let f = File::open(path).map_err(|e| CustomError::ReadWithPath {
-        source: e,
-        path: path.as_ref().to_path_buf(),
-    })?;
-    let f = BufReader::new(f);
-    let mut variant_ids: Vec<String> = Vec::new();
-
-    for (line_idx, line) in f.lines().enumerate() {
-        let line = line.map_err(|e| CustomError::ReadWithPath {
-            source: e,
-            path: path.as_ref().to_path_buf(),
-        })?;
-        let line = line.trim();
-        let fields: Vec<&str> = line.split_whitespace().collect();
-        if fields.len() != SNP_FIELDS {
-            return Err(CustomError::EigenstratSnpFields {
-                line_num: line_idx + 1,
-                n_fields: fields.len(),
-                expected: SNP_FIELDS,
-            });
-        }
-        let snp_id = fields[0].to_string();
-        let chr = fields[1].to_string();
-        variant_ids.push(format!("{}:{}:", chr, snp_id));
-    }
-    Ok(variant_ids)
-}
-
 fn parse_header_block(block: &[u8]) -> Result<Header> {
     let null_pos = block
         .iter()
@@ -272,15 +423,101 @@ fn parse_header_block(block: &[u8]) -> Result<Header> {
     let n_variants = fields[2]
         .parse::<usize>()
         .map_err(|e| CustomError::PackedAncestryMapHeaderV { source: e })?;
-
-    // TO-DO: Verify hashes
-    // let hash_samples = fields[3].parse::<u32>()
-    //     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("invalid sample hash: {e}")))?;
-    // let hash_variants = fields[4].parse::<u32>()
-    //     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("invalid variant hash: {e}")))?;
+    let sample_hash = u32::from_str_radix(fields[3], 16)
+        .map_err(|e| CustomError::PackedAncestryMapHeaderHash { source: e })?;
+    let variant_hash = u32::from_str_radix(fields[4], 16)
+        .map_err(|e| CustomError::PackedAncestryMapHeaderHash { source: e })?;
 
     Ok(Header {
         n_samples,
         n_variants,
+        sample_hash,
+        variant_hash,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_known_good_tgeno(
+        path: &Path,
+        samples: &[String],
+        variants: &[String],
+        sample_hash: u32,
+        variant_hash: u32,
+    ) {
+        let sample_block_size = HEADER_BLOCK_SIZE.max(variants.len().div_ceil(4));
+        let mut header_block = vec![0u8; HEADER_BLOCK_SIZE];
+        let header_str = format!(
+            "TGENO {} {} {:08x} {:08x}",
+            samples.len(),
+            variants.len(),
+            sample_hash,
+            variant_hash
+        );
+        header_block[..header_str.len()].copy_from_slice(header_str.as_bytes());
+
+        let mut file = File::create(path).unwrap();
+        file.write_all(&header_block).unwrap();
+        // One all-Alt (all-zero) sample block per sample is enough to exercise open() end-to-end.
+        for _ in samples {
+            file.write_all(&vec![0u8; sample_block_size]).unwrap();
+        }
+    }
+
+    #[test]
+    fn open_accepts_matching_hashes() {
+        let dir = std::env::temp_dir().join(format!("fastpmr-tgeno-hash-ok-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ind_path = dir.join("test.ind");
+        let snp_path = dir.join("test.snp");
+        let geno_path = dir.join("test.geno");
+
+        std::fs::write(&ind_path, "Sample1 U 0\nSample2 U 0\n").unwrap();
+        std::fs::write(&snp_path, "rs1 1 0.0 1 A G\n").unwrap();
+
+        let samples = vec!["Sample1".to_string(), "Sample2".to_string()];
+        let variants = vec!["rs1".to_string()];
+        write_known_good_tgeno(
+            &geno_path,
+            &samples,
+            &variants,
+            header_hash(&samples),
+            header_hash(&variants),
+        );
+
+        let reader = TransposedPackedAncestryMapReader::open(
+            &ind_path, &geno_path, &snp_path, None, None, true,
+        );
+        assert!(reader.is_ok(), "expected matching hashes to open cleanly");
+    }
+
+    #[test]
+    fn open_rejects_mismatched_hashes_when_verifying() {
+        let dir = std::env::temp_dir().join(format!("fastpmr-tgeno-hash-bad-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ind_path = dir.join("test.ind");
+        let snp_path = dir.join("test.snp");
+        let geno_path = dir.join("test.geno");
+
+        std::fs::write(&ind_path, "Sample1 U 0\nSample2 U 0\n").unwrap();
+        std::fs::write(&snp_path, "rs1 1 0.0 1 A G\n").unwrap();
+
+        let samples = vec!["Sample1".to_string(), "Sample2".to_string()];
+        let variants = vec!["rs1".to_string()];
+        write_known_good_tgeno(&geno_path, &samples, &variants, 0xdead_beef, 0xdead_beef);
+
+        let err = TransposedPackedAncestryMapReader::open(
+            &ind_path, &geno_path, &snp_path, None, None, true,
+        )
+        .unwrap_err();
+        assert!(matches!(err, CustomError::PackedAncestryMapHashMismatch { .. }));
+
+        let reader = TransposedPackedAncestryMapReader::open(
+            &ind_path, &geno_path, &snp_path, None, None, false,
+        );
+        assert!(reader.is_ok(), "mismatch should only warn when verify_hashes is false");
+    }
+}
@@ -0,0 +1,261 @@
+use rust_htslib::bcf::{self, Read as BcfRead};
+use rust_htslib::bcf::record::GenotypeAllele;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::error::{CustomError, Result};
+use crate::model::{Allele, Site};
+use crate::reader::SiteReader;
+use crate::reader::common::select_samples;
+
+/// How [`VcfReader`] handles a multiallelic record, which doesn't map cleanly onto the
+/// biallelic Ref/Het/Alt encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MultiallelicPolicy {
+    /// Drop the record entirely; it never reaches the downstream pipeline as a site.
+    #[default]
+    Skip,
+    /// Keep the record's place in the site stream, but emit every sample as `Missing` rather
+    /// than guessing at a biallelic collapse.
+    Missing,
+}
+
+/// Reads genotypes site-by-site from a `.vcf`, `.vcf.gz`, or `.bcf` file via rust-htslib, so
+/// modern variant-call files can feed the same pairwise pipeline as the PLINK/EIGENSTRAT
+/// readers.
+pub struct VcfReader {
+    reader: bcf::Reader,
+    samples: Vec<String>,
+    sample_indices_to_keep: Option<Vec<usize>>,
+    variant_indices_to_keep: Option<HashSet<usize>>,
+    multiallelic_policy: MultiallelicPolicy,
+    next_variant_idx: usize,
+    n_sites: usize,
+}
+
+impl VcfReader {
+    pub fn open(
+        path: &impl AsRef<Path>,
+        samples_to_keep: Option<HashSet<String>>,
+        variant_indices_to_keep: Option<HashSet<usize>>,
+    ) -> Result<Self> {
+        Self::open_with_multiallelic_policy(
+            path,
+            samples_to_keep,
+            variant_indices_to_keep,
+            MultiallelicPolicy::default(),
+        )
+    }
+
+    pub fn open_with_multiallelic_policy(
+        path: &impl AsRef<Path>,
+        samples_to_keep: Option<HashSet<String>>,
+        variant_indices_to_keep: Option<HashSet<usize>>,
+        multiallelic_policy: MultiallelicPolicy,
+    ) -> Result<Self> {
+        let reader = bcf::Reader::from_path(path).map_err(|source| CustomError::VcfOpen {
+            source,
+            path: path.as_ref().to_path_buf(),
+        })?;
+
+        let samples: Vec<String> = reader
+            .header()
+            .samples()
+            .iter()
+            .map(|s| {
+                std::str::from_utf8(s)
+                    .map(str::to_owned)
+                    .map_err(|source| CustomError::VcfHeader { source })
+            })
+            .collect::<Result<_>>()?;
+        if samples.len() < 2 {
+            return Err(CustomError::SampleCount {
+                n_samples: samples.len(),
+            });
+        }
+
+        let n_sites = count_records(path)?;
+        if n_sites < 1 {
+            return Err(CustomError::VariantCount { n_variants: n_sites });
+        }
+        if let Some(set) = &variant_indices_to_keep
+            && let Some(&bad_idx) = set.iter().max()
+            && bad_idx >= n_sites
+        {
+            return Err(CustomError::VariantIndexHigh {
+                idx: bad_idx + 1,
+                n_variants: n_sites,
+            });
+        }
+
+        let (samples, sample_indices_to_keep) = select_samples(samples, samples_to_keep)?;
+
+        Ok(Self {
+            reader,
+            samples,
+            sample_indices_to_keep,
+            variant_indices_to_keep,
+            multiallelic_policy,
+            next_variant_idx: 0,
+            n_sites,
+        })
+    }
+}
+
+impl VcfReader {
+    fn n_kept_samples(&self) -> usize {
+        match &self.sample_indices_to_keep {
+            Some(indices) => indices.len(),
+            None => self.samples.len(),
+        }
+    }
+}
+
+impl SiteReader for VcfReader {
+    fn samples(&self) -> &[String] {
+        &self.samples
+    }
+
+    fn n_sites(&self) -> usize {
+        match &self.variant_indices_to_keep {
+            Some(set) => set.len(),
+            None => self.n_sites,
+        }
+    }
+}
+
+impl Iterator for VcfReader {
+    type Item = Result<Site>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = self.reader.empty_record();
+        loop {
+            match self.reader.read(&mut record) {
+                None => return None,
+                Some(Err(source)) => return Some(Err(CustomError::VcfRecord { source })),
+                Some(Ok(())) => {}
+            }
+
+            let variant_idx = self.next_variant_idx;
+            self.next_variant_idx += 1;
+
+            let keep = match &self.variant_indices_to_keep {
+                Some(set) => set.contains(&variant_idx),
+                None => true,
+            };
+            if !keep {
+                continue;
+            }
+
+            // Multiallelic sites have GT indices that don't map cleanly onto Ref/Het/Alt.
+            if record.allele_count() > 2 {
+                return match self.multiallelic_policy {
+                    MultiallelicPolicy::Skip => continue,
+                    MultiallelicPolicy::Missing => Some(Ok(Site {
+                        genotypes: vec![Allele::Missing; self.n_kept_samples()],
+                    })),
+                };
+            }
+
+            let genotypes = match record.genotypes() {
+                Ok(genotypes) => genotypes,
+                Err(source) => return Some(Err(CustomError::VcfRecord { source })),
+            };
+
+            let all_genotypes: Vec<Allele> = (0..self.samples.len())
+                .map(|_| Allele::Missing)
+                .collect();
+            let mut all_genotypes = all_genotypes;
+            for (sample_idx, genotype) in all_genotypes.iter_mut().enumerate() {
+                *genotype = decode_gt(&genotypes.get(sample_idx));
+            }
+
+            let genotypes = match &self.sample_indices_to_keep {
+                Some(indices) => indices.iter().map(|&idx| all_genotypes[idx]).collect(),
+                None => all_genotypes,
+            };
+            return Some(Ok(Site { genotypes }));
+        }
+    }
+}
+
+fn decode_gt(genotype: &[GenotypeAllele]) -> Allele {
+    let called: Vec<i32> = genotype
+        .iter()
+        .filter_map(|allele| match allele {
+            GenotypeAllele::Unphased(i) | GenotypeAllele::Phased(i) => Some(*i),
+            GenotypeAllele::UnphasedMissing | GenotypeAllele::PhasedMissing => None,
+        })
+        .collect();
+
+    match called.as_slice() {
+        [0, 0] => Allele::Ref,
+        [1, 1] => Allele::Alt,
+        [0, 1] | [1, 0] => Allele::Het,
+        _ => Allele::Missing,
+    }
+}
+
+fn count_records(path: &impl AsRef<Path>) -> Result<usize> {
+    let mut reader = bcf::Reader::from_path(path).map_err(|source| CustomError::VcfOpen {
+        source,
+        path: path.as_ref().to_path_buf(),
+    })?;
+    let mut record = reader.empty_record();
+    let mut n = 0usize;
+    loop {
+        match reader.read(&mut record) {
+            None => return Ok(n),
+            Some(Err(source)) => return Err(CustomError::VcfRecord { source }),
+            Some(Ok(())) => n += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_gt_maps_homozygous_calls() {
+        assert_eq!(
+            decode_gt(&[GenotypeAllele::Unphased(0), GenotypeAllele::Unphased(0)]),
+            Allele::Ref
+        );
+        assert_eq!(
+            decode_gt(&[GenotypeAllele::Unphased(1), GenotypeAllele::Unphased(1)]),
+            Allele::Alt
+        );
+    }
+
+    #[test]
+    fn decode_gt_maps_heterozygous_calls_regardless_of_phasing_or_order() {
+        assert_eq!(
+            decode_gt(&[GenotypeAllele::Unphased(0), GenotypeAllele::Unphased(1)]),
+            Allele::Het
+        );
+        assert_eq!(
+            decode_gt(&[GenotypeAllele::Phased(1), GenotypeAllele::Unphased(0)]),
+            Allele::Het
+        );
+    }
+
+    #[test]
+    fn decode_gt_treats_partial_missing_as_missing() {
+        // Only one allele called, the other missing -- not a clean Ref/Het/Alt call.
+        assert_eq!(
+            decode_gt(&[
+                GenotypeAllele::Unphased(0),
+                GenotypeAllele::UnphasedMissing
+            ]),
+            Allele::Missing
+        );
+        assert_eq!(
+            decode_gt(&[
+                GenotypeAllele::UnphasedMissing,
+                GenotypeAllele::PhasedMissing
+            ]),
+            Allele::Missing
+        );
+    }
+}
@@ -0,0 +1,12 @@
+pub mod plink_bed;
+pub mod transposed_packedancestrymap;
+
+/// Per-variant metadata needed to round-trip a `.bim`/`.snp` sidecar file. `fastpmr` only ever
+/// tracks genotypes as [`crate::model::Allele`] (Ref/Het/Alt/Missing, not literal bases), so the
+/// allele columns written out are placeholders rather than the original ref/alt calls.
+pub struct VariantMeta {
+    pub id: String,
+    pub chrom: String,
+    pub genetic_pos_cm: f64,
+    pub physical_pos: u64,
+}
@@ -0,0 +1,128 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::error::{CustomError, Result};
+use crate::model::{Allele, Site};
+use crate::writer::VariantMeta;
+
+const BED_MAGIC: [u8; 2] = [0x6c, 0x1b];
+const BED_SNP_MAJOR: u8 = 0x01;
+
+/// Writes `prefix.bed`/`prefix.bim`/`prefix.fam`, inverting the decoding done by
+/// `reader::plink::PlinkBedReader`. `sites` must yield exactly `variants.len()` sites, each with
+/// `samples.len()` genotypes.
+pub fn write_bed(
+    prefix: impl AsRef<Path>,
+    samples: &[String],
+    variants: &[VariantMeta],
+    sites: impl IntoIterator<Item = Result<Site>>,
+) -> Result<()> {
+    let prefix = prefix.as_ref();
+    write_fam(&with_extension(prefix, "fam"), samples)?;
+    write_bim(&with_extension(prefix, "bim"), variants)?;
+
+    let bed_path = with_extension(prefix, "bed");
+    let f = File::create(&bed_path).map_err(|e| CustomError::Write {
+        source: e,
+        path: bed_path.clone(),
+    })?;
+    let mut w = BufWriter::new(f);
+    w.write_all(&BED_MAGIC).map_err(|e| CustomError::Write {
+        source: e,
+        path: bed_path.clone(),
+    })?;
+    w.write_all(&[BED_SNP_MAJOR]).map_err(|e| CustomError::Write {
+        source: e,
+        path: bed_path.clone(),
+    })?;
+
+    let bytes_per_variant = samples.len().div_ceil(4);
+    let mut n_written = 0usize;
+    for site in sites {
+        let site = site?;
+        let block = encode_variant_block(&site.genotypes, bytes_per_variant);
+        w.write_all(&block).map_err(|e| CustomError::Write {
+            source: e,
+            path: bed_path.clone(),
+        })?;
+        n_written += 1;
+    }
+    w.flush().map_err(|e| CustomError::Write {
+        source: e,
+        path: bed_path.clone(),
+    })?;
+
+    if n_written != variants.len() {
+        return Err(CustomError::VariantCount {
+            n_variants: n_written,
+        });
+    }
+    Ok(())
+}
+
+fn with_extension(prefix: &Path, ext: &str) -> std::path::PathBuf {
+    let mut s = prefix.as_os_str().to_os_string();
+    s.push(".");
+    s.push(ext);
+    s.into()
+}
+
+fn write_fam(path: &Path, samples: &[String]) -> Result<()> {
+    let f = File::create(path).map_err(|e| CustomError::Write {
+        source: e,
+        path: path.to_path_buf(),
+    })?;
+    let mut w = BufWriter::new(f);
+    for sample in samples {
+        writeln!(w, "0 {sample} 0 0 0 -9").map_err(|e| CustomError::Write {
+            source: e,
+            path: path.to_path_buf(),
+        })?;
+    }
+    w.flush().map_err(|e| CustomError::Write {
+        source: e,
+        path: path.to_path_buf(),
+    })
+}
+
+fn write_bim(path: &Path, variants: &[VariantMeta]) -> Result<()> {
+    let f = File::create(path).map_err(|e| CustomError::Write {
+        source: e,
+        path: path.to_path_buf(),
+    })?;
+    let mut w = BufWriter::new(f);
+    for variant in variants {
+        writeln!(
+            w,
+            "{} {} {} {} A G",
+            variant.chrom, variant.id, variant.genetic_pos_cm, variant.physical_pos
+        )
+        .map_err(|e| CustomError::Write {
+            source: e,
+            path: path.to_path_buf(),
+        })?;
+    }
+    w.flush().map_err(|e| CustomError::Write {
+        source: e,
+        path: path.to_path_buf(),
+    })
+}
+
+/// Packs up to four samples' genotypes per byte, little-endian within the byte, using the PLINK
+/// convention `00=Ref,01=Missing,10=Het,11=Alt`.
+fn encode_variant_block(genotypes: &[Allele], bytes_per_variant: usize) -> Vec<u8> {
+    let mut block = vec![0u8; bytes_per_variant];
+    for (sample_idx, &allele) in genotypes.iter().enumerate() {
+        let code: u8 = match allele {
+            Allele::Ref => 0b00,
+            Allele::Missing => 0b01,
+            Allele::Het => 0b10,
+            Allele::Alt => 0b11,
+        };
+        let byte_idx = sample_idx / 4;
+        let shift = (sample_idx % 4) * 2;
+        block[byte_idx] |= code << shift;
+    }
+    block
+}
@@ -0,0 +1,133 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::error::{CustomError, Result};
+use crate::model::{Allele, Site};
+use crate::reader::common::header_hash;
+use crate::writer::VariantMeta;
+
+const HEADER_BLOCK_SIZE: usize = 48;
+
+/// Writes `prefix.geno`/`prefix.ind`/`prefix.snp` in the transposed (sample-major) PackedAncestryMap
+/// `TGENO` format, inverting the decoding done by
+/// `reader::transposed_packedancestrymap::TransposedPackedAncestryMapReader::genotypes_for_variant`.
+/// Because the on-disk layout is sample-major while `sites` yields genotypes variant-by-variant,
+/// the full matrix is buffered in memory before being transposed onto disk.
+pub fn write_tgeno(
+    prefix: impl AsRef<Path>,
+    samples: &[String],
+    variants: &[VariantMeta],
+    sites: impl IntoIterator<Item = Result<Site>>,
+) -> Result<()> {
+    let prefix = prefix.as_ref();
+    write_ind(&with_extension(prefix, "ind"), samples)?;
+    write_snp(&with_extension(prefix, "snp"), variants)?;
+
+    let mut all_sites: Vec<Vec<Allele>> = Vec::with_capacity(variants.len());
+    for site in sites {
+        all_sites.push(site?.genotypes);
+    }
+    if all_sites.len() != variants.len() {
+        return Err(CustomError::VariantCount {
+            n_variants: all_sites.len(),
+        });
+    }
+
+    let geno_path = with_extension(prefix, "geno");
+    let f = File::create(&geno_path).map_err(|e| CustomError::Write {
+        source: e,
+        path: geno_path.clone(),
+    })?;
+    let mut w = BufWriter::new(f);
+
+    let n_samples = samples.len();
+    let n_variants = variants.len();
+    let sample_block_size = HEADER_BLOCK_SIZE.max(n_variants.div_ceil(4));
+
+    // Real sample/variant hashes, not placeholders, so the file this tool just wrote can be
+    // read back by this same tool (or EIGENSOFT) with hash verification turned on.
+    let variant_ids: Vec<String> = variants.iter().map(|v| v.id.clone()).collect();
+    let sample_hash = header_hash(samples);
+    let variant_hash = header_hash(&variant_ids);
+
+    let mut header = vec![0u8; HEADER_BLOCK_SIZE];
+    let header_str =
+        format!("TGENO {n_samples} {n_variants} {sample_hash:08x} {variant_hash:08x}");
+    header[..header_str.len()].copy_from_slice(header_str.as_bytes());
+    w.write_all(&header).map_err(|e| CustomError::Write {
+        source: e,
+        path: geno_path.clone(),
+    })?;
+
+    for sample_idx in 0..n_samples {
+        let mut block = vec![0u8; sample_block_size];
+        for (variant_idx, genotypes) in all_sites.iter().enumerate() {
+            let code: u8 = match genotypes[sample_idx] {
+                Allele::Alt => 0b00,
+                Allele::Het => 0b01,
+                Allele::Ref => 0b10,
+                Allele::Missing => 0b11,
+            };
+            let byte_idx = variant_idx / 4;
+            let shift = 6 - 2 * (variant_idx % 4);
+            block[byte_idx] |= code << shift;
+        }
+        w.write_all(&block).map_err(|e| CustomError::Write {
+            source: e,
+            path: geno_path.clone(),
+        })?;
+    }
+    w.flush().map_err(|e| CustomError::Write {
+        source: e,
+        path: geno_path,
+    })
+}
+
+fn with_extension(prefix: &Path, ext: &str) -> std::path::PathBuf {
+    let mut s = prefix.as_os_str().to_os_string();
+    s.push(".");
+    s.push(ext);
+    s.into()
+}
+
+fn write_ind(path: &Path, samples: &[String]) -> Result<()> {
+    let f = File::create(path).map_err(|e| CustomError::Write {
+        source: e,
+        path: path.to_path_buf(),
+    })?;
+    let mut w = BufWriter::new(f);
+    for sample in samples {
+        writeln!(w, "{sample} U ???").map_err(|e| CustomError::Write {
+            source: e,
+            path: path.to_path_buf(),
+        })?;
+    }
+    w.flush().map_err(|e| CustomError::Write {
+        source: e,
+        path: path.to_path_buf(),
+    })
+}
+
+fn write_snp(path: &Path, variants: &[VariantMeta]) -> Result<()> {
+    let f = File::create(path).map_err(|e| CustomError::Write {
+        source: e,
+        path: path.to_path_buf(),
+    })?;
+    let mut w = BufWriter::new(f);
+    for variant in variants {
+        writeln!(
+            w,
+            "{} {} {} {} A G",
+            variant.id, variant.chrom, variant.genetic_pos_cm, variant.physical_pos
+        )
+        .map_err(|e| CustomError::Write {
+            source: e,
+            path: path.to_path_buf(),
+        })?;
+    }
+    w.flush().map_err(|e| CustomError::Write {
+        source: e,
+        path: path.to_path_buf(),
+    })
+}
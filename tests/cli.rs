@@ -57,6 +57,55 @@ fn transposed_packedancestrymap_cli_generates_outputs() {
     );
 }
 
+#[test]
+fn packedancestrymap_cli_reads_gzip_compressed_input() {
+    let dataset = common::create_dataset_gzip(common::GenoFormat::Packed, "packed-gzip").unwrap();
+    if dataset.output_dir.exists() {
+        fs::remove_dir_all(&dataset.output_dir).unwrap();
+    }
+
+    let expected_pairs = common::expected_pair_stats_all_variants();
+    let output = run_fastpmr(&dataset, None, false, None);
+    assert!(
+        output.status.success(),
+        "fastpmr failed: stdout={} stderr={}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let records = assert_outputs(&dataset.output_dir, &expected_pairs);
+    assert_eq!(
+        records.len(),
+        expected_pairs.len(),
+        "unexpected number of pairwise records"
+    );
+}
+
+#[test]
+fn transposed_packedancestrymap_cli_reads_gzip_compressed_input() {
+    let dataset =
+        common::create_dataset_gzip(common::GenoFormat::Transposed, "transposed-gzip").unwrap();
+    if dataset.output_dir.exists() {
+        fs::remove_dir_all(&dataset.output_dir).unwrap();
+    }
+
+    let expected_pairs = common::expected_pair_stats_all_variants();
+    let output = run_fastpmr(&dataset, None, false, None);
+    assert!(
+        output.status.success(),
+        "fastpmr failed: stdout={} stderr={}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let records = assert_outputs(&dataset.output_dir, &expected_pairs);
+    assert_eq!(
+        records.len(),
+        expected_pairs.len(),
+        "unexpected number of pairwise records"
+    );
+}
+
 #[test]
 fn packedancestrymap_cli_generates_npz_outputs() {
     let dataset = common::create_dataset(common::GenoFormat::Packed, "packed-npz").unwrap();
@@ -170,6 +219,63 @@ fn sample_pairs_csv_with_unknown_sample_fails() {
     );
 }
 
+#[test]
+fn tgeno_export_round_trips_through_prefix() {
+    let dataset = common::create_dataset(common::GenoFormat::Packed, "tgeno-export").unwrap();
+    if dataset.output_dir.exists() {
+        fs::remove_dir_all(&dataset.output_dir).unwrap();
+    }
+
+    let export_prefix = dataset.output_dir.join("exported");
+    let export_output = Command::new(env!("CARGO_BIN_EXE_fastpmr"))
+        .arg("--prefix")
+        .arg(dataset.prefix.as_os_str())
+        .arg("--output-directory")
+        .arg(dataset.output_dir.as_os_str())
+        .arg("--export-tgeno")
+        .arg(export_prefix.as_os_str())
+        .output()
+        .expect("failed to run fastpmr --export-tgeno");
+    assert!(
+        export_output.status.success(),
+        "export failed: stdout={} stderr={}",
+        String::from_utf8_lossy(&export_output.stdout),
+        String::from_utf8_lossy(&export_output.stderr)
+    );
+
+    // Read the just-exported TGENO files back through --prefix: if write_tgeno's header hashes
+    // didn't match what the reader recomputes, this would fail with PackedAncestryMapHashMismatch.
+    let readback_output_dir = dataset.output_dir.join("readback");
+    let readback_output = Command::new(env!("CARGO_BIN_EXE_fastpmr"))
+        .arg("--prefix")
+        .arg(&export_prefix)
+        .arg("--output-directory")
+        .arg(&readback_output_dir)
+        .output()
+        .expect("failed to run fastpmr on the exported TGENO dataset");
+    assert!(
+        readback_output.status.success(),
+        "reading exported TGENO back failed: stdout={} stderr={}",
+        String::from_utf8_lossy(&readback_output.stdout),
+        String::from_utf8_lossy(&readback_output.stderr)
+    );
+
+    let records = read_records(&readback_output_dir.join("mismatch_rates.csv"));
+    assert!(
+        !records.is_empty(),
+        "expected at least one mismatch rate record after round-tripping through --export-tgeno"
+    );
+    for record in &records {
+        assert_eq!(record.overlap, common::expected_overlap_all());
+        assert!(
+            (record.rate - common::expected_rate_all()).abs() < 1e-6,
+            "unexpected mismatch rate after round-trip: got {}, expected {}",
+            record.rate,
+            common::expected_rate_all()
+        );
+    }
+}
+
 fn run_fastpmr(
     dataset: &common::Dataset,
     variant_spec: Option<&str>,
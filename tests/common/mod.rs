@@ -1,3 +1,5 @@
+use flate2::Compression;
+use flate2::write::GzEncoder;
 use std::fs::{self, File};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
@@ -17,6 +19,31 @@ const MISSING: u8 = 0b11;
 
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
+const SAMPLE_IDS: [&str; N_SAMPLES] = ["Sample1", "Sample2"];
+
+// Mirrors `reader::common::eigenstrat::header_hash` exactly, so the fixtures this module writes
+// pass fastpmr's EIGENSOFT header-hash verification. Duplicated rather than shared because this
+// crate has no lib target for integration tests to link against; only the compiled
+// `fastpmr` binary is exercised (via `CARGO_BIN_EXE_fastpmr`).
+const HASH_BYTE_MULTIPLIER: u32 = 23;
+const HASH_ACCUMULATE_MULTIPLIER: u32 = 17;
+
+fn header_hash(ids: &[String]) -> u32 {
+    let mut acc: u32 = 0;
+    for id in ids {
+        let mut h: u32 = 0;
+        for &c in id.as_bytes() {
+            h = h.wrapping_mul(HASH_BYTE_MULTIPLIER).wrapping_add(c as u32);
+        }
+        acc = acc.wrapping_mul(HASH_ACCUMULATE_MULTIPLIER).wrapping_add(h);
+    }
+    acc
+}
+
+fn variant_ids(n_variants: usize) -> Vec<String> {
+    (0..n_variants).map(|idx| format!("rs{}", idx + 1)).collect()
+}
+
 #[derive(Clone, Copy)]
 pub enum GenoFormat {
     Packed,
@@ -52,6 +79,25 @@ pub fn create_dataset(format: GenoFormat, label: &str) -> io::Result<Dataset> {
     Ok(Dataset { prefix, output_dir })
 }
 
+/// Builds the same dataset as [`create_dataset`], then gzip-compresses the `.ind`, `.snp`, and
+/// `.geno` files in place (same file names, no `.gz` suffix) to exercise fastpmr's
+/// content-sniffed gzip/BGZF support for EIGENSTRAT and PackedAncestryMap input.
+pub fn create_dataset_gzip(format: GenoFormat, label: &str) -> io::Result<Dataset> {
+    let dataset = create_dataset(format, label)?;
+    for ext in ["ind", "snp", "geno"] {
+        gzip_in_place(dataset.prefix.with_extension(ext))?;
+    }
+    Ok(dataset)
+}
+
+fn gzip_in_place(path: PathBuf) -> io::Result<()> {
+    let raw = fs::read(&path)?;
+    let mut encoder = GzEncoder::new(File::create(&path)?, Compression::default());
+    encoder.write_all(&raw)?;
+    encoder.finish()?;
+    Ok(())
+}
+
 pub fn expected_overlap_all() -> u64 {
     (CORE_VARIANTS + EXTRA_IDENTICAL_VARIANTS) as u64
 }
@@ -106,7 +152,15 @@ fn write_snp(path: impl AsRef<Path>) -> io::Result<()> {
 fn write_geno(path: impl AsRef<Path>, variants: &[[u8; N_SAMPLES]]) -> io::Result<()> {
     let mut file = File::create(path)?;
     let block_size = 48usize.max(N_SAMPLES.div_ceil(4));
-    let header_str = format!("GENO {} {} 0 0", N_SAMPLES, variants.len());
+    let sample_hash = header_hash(&SAMPLE_IDS.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+    let variant_hash = header_hash(&variant_ids(variants.len()));
+    let header_str = format!(
+        "GENO {} {} {:08x} {:08x}",
+        N_SAMPLES,
+        variants.len(),
+        sample_hash,
+        variant_hash
+    );
     let mut header_block = vec![0u8; block_size];
     header_block[..header_str.len()].copy_from_slice(header_str.as_bytes());
     header_block[header_str.len()] = 0;
@@ -135,7 +189,15 @@ fn write_geno(path: impl AsRef<Path>, variants: &[[u8; N_SAMPLES]]) -> io::Resul
 
 fn write_tgeno(path: impl AsRef<Path>, variants: &[[u8; N_SAMPLES]]) -> io::Result<()> {
     let mut file = File::create(path)?;
-    let header_str = format!("TGENO {} {} 0 0", N_SAMPLES, variants.len());
+    let sample_hash = header_hash(&SAMPLE_IDS.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+    let variant_hash = header_hash(&variant_ids(variants.len()));
+    let header_str = format!(
+        "TGENO {} {} {:08x} {:08x}",
+        N_SAMPLES,
+        variants.len(),
+        sample_hash,
+        variant_hash
+    );
     let mut header_block = vec![0u8; 48];
     header_block[..header_str.len()].copy_from_slice(header_str.as_bytes());
     header_block[header_str.len()] = 0;